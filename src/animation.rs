@@ -0,0 +1,119 @@
+//! Time based animation of the shifts, with selectable easing curves.
+use crate::puzzle::{Direction, Shift};
+use std::f64::consts::PI;
+use wasm_bindgen::prelude::*;
+
+/// How an animation progresses over its duration.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    /// Starts fast and slows down towards the end
+    #[default]
+    EaseOut,
+    /// Overshoots slightly and settles like a damped spring
+    Spring,
+}
+
+impl Easing {
+    /// Maps the elapsed fraction of the duration to the progress of the animation, both
+    /// starting at 0 and ending at 1.
+    pub fn apply(&self, t: f64) -> f64 {
+        if t >= 1.0 {
+            return 1.0;
+        }
+        let t = t.max(0.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::Spring => 1.0 - (-8.0 * t).exp() * (3.0 * PI * t).cos(),
+        }
+    }
+}
+
+/// Interpolates between two values over a duration in milliseconds.
+#[derive(Clone, Debug)]
+pub struct Tween {
+    from: f64,
+    to: f64,
+    elapsed: f64,
+    duration: f64,
+    easing: Easing,
+}
+
+impl Tween {
+    pub fn new(from: f64, to: f64, duration: f64, easing: Easing) -> Tween {
+        Tween {
+            from,
+            to,
+            elapsed: 0.0,
+            duration,
+            easing,
+        }
+    }
+
+    /// Advances the animation by `dt` milliseconds, returning the new value.
+    pub fn advance(&mut self, dt: f64) -> f64 {
+        self.elapsed += dt;
+        self.value()
+    }
+
+    pub fn value(&self) -> f64 {
+        let t = if self.duration > 0.0 {
+            self.elapsed / self.duration
+        } else {
+            1.0
+        };
+        self.from + (self.to - self.from) * self.easing.apply(t)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+/// An animated shift of a row or column, measured in fractions of a tile.
+pub struct ShiftAction {
+    pub idx: u8,
+    pub direction: Direction,
+    pub progress: Tween,
+    /// Whether the shift is applied at the end, rather than the line returning to its place
+    pub commit: bool,
+}
+
+impl ShiftAction {
+    /// A whole shift from the grid position.
+    pub fn create(shift: &Shift, duration: f64, easing: Easing) -> ShiftAction {
+        ShiftAction {
+            idx: shift.idx,
+            direction: shift.direction,
+            progress: Tween::new(0.0, 1.0, duration, easing),
+            commit: true,
+        }
+    }
+
+    /// Finishes a line released part of the way along `shift`, by completing the shift if
+    /// `commit` is set or by moving the line back otherwise.
+    pub fn release(
+        shift: &Shift,
+        progress: f64,
+        commit: bool,
+        duration: f64,
+        easing: Easing,
+    ) -> ShiftAction {
+        let target = if commit { 1.0 } else { 0.0 };
+        ShiftAction {
+            idx: shift.idx,
+            direction: shift.direction,
+            progress: Tween::new(progress, target, duration, easing),
+            commit,
+        }
+    }
+
+    pub fn shift(&self) -> Shift {
+        Shift {
+            idx: self.idx,
+            direction: self.direction,
+        }
+    }
+}
@@ -0,0 +1,244 @@
+//! Solves and analyses positions offline, and generates scrambles like the web version does.
+//!
+//! ```text
+//! solve CODE                    solve the position of a position code
+//! solve --grid FILE             solve a position in the grid notation, - for standard input
+//! solve --scramble [--size N] [--difficulty easy|medium|hard | --steps N] [--seed N] [--count N]
+//! ```
+//!
+//! Both take `--max-depth N`, the longest solution to search for, and `--budget N`, the most
+//! positions a single search for a solution or for the length of a scramble's shortest
+//! solution expands before giving up. The searches that pick a scramble's position keep the
+//! web version's bounds, so that the same seeds give the same scrambles.
+use puzzle::code;
+use puzzle::config::{MAX_SHUFFLE_STEPS, MAX_SIZE, MIN_SHUFFLE_STEPS, MIN_SIZE};
+use puzzle::notation::format_moves;
+use puzzle::puzzle::PuzzleState;
+use puzzle::rng::{random_seed, SeededRng};
+use puzzle::shuffle::{scramble, shuffle, Difficulty};
+use puzzle::solver::{solve_within, SearchFailure, MAX_DEPTH, SEARCH_BUDGET};
+use puzzle::text;
+use std::fs;
+use std::io::{self, Read};
+use std::process;
+use std::time::Instant;
+
+const USAGE: &str = "\
+Usage: solve CODE [--max-depth N] [--budget N]
+       solve --grid FILE [--max-depth N] [--budget N]
+       solve --scramble [--size N] [--difficulty easy|medium|hard | --steps N] [--seed N] [--count N] [--max-depth N] [--budget N]";
+
+/// The default `--budget`, far beyond the game's since nothing waits on the search here.
+const DEFAULT_BUDGET: usize = 100 * SEARCH_BUDGET;
+
+/// What to do, with the options that apply to it.
+enum Task {
+    Solve {
+        position: Position,
+        max_depth: usize,
+        budget: usize,
+    },
+    Scramble {
+        size: i8,
+        difficulty: Option<Difficulty>,
+        steps: i8,
+        seed: Option<u32>,
+        count: u32,
+        max_depth: usize,
+        budget: usize,
+    },
+}
+
+enum Position {
+    Code(String),
+    /// A file in the grid notation, or standard input for `-`
+    Grid(String),
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Task, String> {
+    let mut position = None;
+    let mut scrambling = false;
+    let mut max_depth = MAX_DEPTH;
+    let mut budget = DEFAULT_BUDGET;
+    let mut size = 3;
+    let mut difficulty = None;
+    let mut steps = 10;
+    let mut seed = None;
+    let mut count = 1;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--grid" => position = Some(Position::Grid(value()?)),
+            "--max-depth" => {
+                max_depth = value()?
+                    .parse()
+                    .map_err(|_| "Invalid maximum depth".to_string())?
+            }
+            "--budget" => {
+                budget = value()?
+                    .parse()
+                    .map_err(|_| "Invalid search budget".to_string())?
+            }
+            "--scramble" => scrambling = true,
+            "--size" => match value()?.parse() {
+                Ok(n) if (MIN_SIZE..=MAX_SIZE).contains(&n) => size = n,
+                _ => return Err(format!("The size must be {} to {}", MIN_SIZE, MAX_SIZE)),
+            },
+            "--difficulty" => difficulty = Some(value()?.parse()?),
+            "--steps" => match value()?.parse() {
+                Ok(n) if (MIN_SHUFFLE_STEPS..=MAX_SHUFFLE_STEPS).contains(&n) => steps = n,
+                _ => {
+                    return Err(format!(
+                        "The steps must be {} to {}",
+                        MIN_SHUFFLE_STEPS, MAX_SHUFFLE_STEPS
+                    ))
+                }
+            },
+            "--seed" => seed = Some(value()?.parse().map_err(|_| "Invalid seed".to_string())?),
+            "--count" => count = value()?.parse().map_err(|_| "Invalid count".to_string())?,
+            "--help" | "-h" => return Err(USAGE.to_string()),
+            _ if !arg.starts_with('-') && position.is_none() => {
+                position = Some(Position::Code(arg))
+            }
+            _ => return Err(format!("Unknown option {}\n{}", arg, USAGE)),
+        }
+    }
+
+    match (scrambling, position) {
+        (true, None) => Ok(Task::Scramble {
+            size,
+            difficulty,
+            steps,
+            seed,
+            count,
+            max_depth,
+            budget,
+        }),
+        (false, Some(position)) => Ok(Task::Solve {
+            position,
+            max_depth,
+            budget,
+        }),
+        (true, Some(_)) => Err("Scrambling does not take a position".to_string()),
+        (false, None) => Err(USAGE.to_string()),
+    }
+}
+
+fn read_position(position: &Position) -> Result<PuzzleState, String> {
+    match position {
+        Position::Code(text) => code::decode(text).ok_or(format!("Invalid position code {}", text)),
+        Position::Grid(path) => {
+            let mut grid = String::new();
+            let read = if path == "-" {
+                io::stdin().read_to_string(&mut grid).map(|_| ())
+            } else {
+                fs::read_to_string(path).map(|text| grid = text)
+            };
+            read.map_err(|error| format!("Cannot read {}: {}", path, error))?;
+            text::parse_grid(&grid).ok_or(format!("{} does not hold a valid grid", path))
+        }
+    }
+}
+
+/// Prints the position and its shortest solution with the effort it took to find it.
+fn analyse(state: &PuzzleState, max_depth: usize, budget: usize) -> Result<(), String> {
+    println!("{}", text::format_grid(state));
+    println!("Code:     {}", code::encode(state));
+
+    let started = Instant::now();
+    let solution = solve_within(state, max_depth, budget);
+    let elapsed = started.elapsed();
+
+    let solution = solution.map_err(|failure| match failure {
+        SearchFailure::Exhausted { expanded } => format!(
+            "There is no solution within {} moves ({} positions expanded)",
+            max_depth, expanded
+        ),
+        SearchFailure::OutOfBudget { expanded } => format!(
+            "Gave up after expanding {} positions without a solution within {} moves",
+            expanded, max_depth
+        ),
+    })?;
+    println!("Solution: {}", format_moves(&solution.shifts));
+    println!("Length:   {}", solution.shifts.len());
+    println!("Expanded: {}", solution.expanded);
+    println!("Time:     {:.3} ms", elapsed.as_secs_f64() * 1000.0);
+    Ok(())
+}
+
+/// Prints scrambles from consecutive seeds, each with the code of its position, the moves
+/// making it and the length of its shortest solution, which is unknown if the search runs
+/// out of budget and only bounded if there is none within `max_depth`.
+fn print_scrambles(
+    size: i8,
+    difficulty: Option<Difficulty>,
+    steps: i8,
+    first_seed: u32,
+    count: u32,
+    max_depth: usize,
+    budget: usize,
+) {
+    for seed in (0..count).map(|i| first_seed.wrapping_add(i)) {
+        let mut rng = SeededRng::new(seed as u64);
+        let shifts = match difficulty {
+            Some(difficulty) => scramble(size, difficulty, &mut rng),
+            None => shuffle(size, steps, &mut rng),
+        };
+        let mut state = PuzzleState::new(size);
+        for shift in &shifts {
+            state.apply(shift);
+        }
+        let optimal = match solve_within(&state, max_depth, budget) {
+            Ok(solution) => solution.shifts.len().to_string(),
+            Err(SearchFailure::Exhausted { .. }) => format!("over {}", max_depth),
+            Err(SearchFailure::OutOfBudget { .. }) => "unknown".to_string(),
+        };
+        println!(
+            "seed {} code {} optimal {} scramble {}",
+            seed,
+            code::encode(&state),
+            optimal,
+            format_moves(&shifts)
+        );
+    }
+}
+
+fn main() {
+    let task = parse_args(std::env::args().skip(1)).unwrap_or_else(|message| {
+        eprintln!("{}", message);
+        process::exit(2);
+    });
+
+    let result = match task {
+        Task::Solve {
+            position,
+            max_depth,
+            budget,
+        } => read_position(&position).and_then(|state| analyse(&state, max_depth, budget)),
+        Task::Scramble {
+            size,
+            difficulty,
+            steps,
+            seed,
+            count,
+            max_depth,
+            budget,
+        } => {
+            print_scrambles(
+                size,
+                difficulty,
+                steps,
+                seed.unwrap_or_else(random_seed),
+                count,
+                max_depth,
+                budget,
+            );
+            Ok(())
+        }
+    };
+    if let Err(message) = result {
+        eprintln!("{}", message);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,370 @@
+//! Plays the puzzle in a terminal, with the same rules, scrambles and solver as the web version.
+//!
+//! Run with `cargo run --features tui --bin tui -- [--size N] [--seed N] [--steps N]
+//! [--difficulty easy|medium|hard] [--code CODE]`.
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::Print;
+use crossterm::{cursor, execute, queue, terminal};
+use puzzle::code;
+use puzzle::config::{MAX_SHUFFLE_STEPS, MAX_SIZE, MIN_SHUFFLE_STEPS, MIN_SIZE};
+use puzzle::cursor::Cursor;
+use puzzle::history::History;
+use puzzle::notation::format_moves;
+use puzzle::puzzle::{Direction, PuzzleState, Shift};
+use puzzle::rng::{random_seed, SeededRng};
+use puzzle::shuffle::{scramble, shuffle, Difficulty};
+use puzzle::solver::{solve_for_game, MAX_AUTO_SOLVE_LENGTH};
+use puzzle::stats::Stats;
+use puzzle::text;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::process;
+use std::time::{Duration, Instant};
+
+/// How often the timer is redrawn while no keys are pressed
+const TICK: Duration = Duration::from_millis(100);
+/// How long every move of an automatic solution is shown, in milliseconds
+const SOLVE_STEP: f64 = 300.0;
+
+const USAGE: &str =
+    "Usage: tui [--size N] [--seed N] [--steps N] [--difficulty easy|medium|hard] [--code CODE]";
+const CONTROLS: &str = "\
+arrows move the cursor, shift+arrows or enter and arrows shift its row or column
+u undo   r redo   h hint   s solve   n new game   q quit";
+
+struct Options {
+    size: i8,
+    steps: i8,
+    seed: Option<u32>,
+    difficulty: Option<Difficulty>,
+    code: Option<String>,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options {
+            size: 3,
+            steps: 10,
+            seed: None,
+            difficulty: None,
+            code: None,
+        };
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+            match arg.as_str() {
+                "--size" => match value()?.parse() {
+                    Ok(size) if (MIN_SIZE..=MAX_SIZE).contains(&size) => options.size = size,
+                    _ => return Err(format!("The size must be {} to {}", MIN_SIZE, MAX_SIZE)),
+                },
+                "--steps" => match value()?.parse() {
+                    Ok(steps) if (MIN_SHUFFLE_STEPS..=MAX_SHUFFLE_STEPS).contains(&steps) => {
+                        options.steps = steps
+                    }
+                    _ => {
+                        return Err(format!(
+                            "The steps must be {} to {}",
+                            MIN_SHUFFLE_STEPS, MAX_SHUFFLE_STEPS
+                        ))
+                    }
+                },
+                "--seed" => {
+                    options.seed = Some(value()?.parse().map_err(|_| "Invalid seed".to_string())?)
+                }
+                "--difficulty" => options.difficulty = Some(value()?.parse()?),
+                "--code" => options.code = Some(value()?),
+                "--help" | "-h" => return Err(USAGE.to_string()),
+                _ => return Err(format!("Unknown option {}\n{}", arg, USAGE)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+struct Game {
+    state: PuzzleState,
+    stats: Stats,
+    history: History,
+    cursor: Cursor,
+    /// The remaining moves of an automatic solution, played one at a time
+    solving: VecDeque<Shift>,
+    /// Whether the solver is playing, which does not count as the player solving it
+    auto_solving: bool,
+    since_step: f64,
+    /// The moves that made the position from the solved layout, unless it was opened from a code
+    trail: Option<Vec<Shift>>,
+    /// A line of feedback shown below the board
+    status: String,
+}
+
+impl Game {
+    fn open(state: PuzzleState, status: String) -> Game {
+        Game {
+            state,
+            stats: Stats::default(),
+            history: History::default(),
+            cursor: Cursor::new(),
+            solving: VecDeque::new(),
+            auto_solving: false,
+            since_step: 0.0,
+            trail: None,
+            status,
+        }
+    }
+
+    fn scrambled(options: &Options, seed: u32) -> Game {
+        let mut rng = SeededRng::new(seed as u64);
+        let shifts = match options.difficulty {
+            Some(difficulty) => scramble(options.size, difficulty, &mut rng),
+            None => shuffle(options.size, options.steps, &mut rng),
+        };
+        let mut state = PuzzleState::new(options.size);
+        for shift in &shifts {
+            state.apply(shift);
+        }
+        let mut game = Game::open(state, format!("Shuffled with seed {}", seed));
+        game.trail = Some(shifts);
+        game
+    }
+
+    fn is_solved(&self) -> bool {
+        self.state.is_solved()
+    }
+
+    /// Makes a move of the player, if it is legal.
+    fn play(&mut self, shift: Shift) {
+        if self.is_solved() || !self.solving.is_empty() {
+            return;
+        }
+        if !self.state.apply(&shift) {
+            self.status = format!("{} is not a legal move", shift);
+            return;
+        }
+        self.history.record(shift);
+        self.extend_trail(shift);
+        self.stats.record_move();
+        self.status = format!("Moved {}", shift);
+        self.check_solved();
+    }
+
+    /// Makes a move that is not counted, such as an undo or a step of a solution.
+    fn replay(&mut self, shift: Shift) {
+        self.state.apply(&shift);
+        self.extend_trail(shift);
+        self.check_solved();
+    }
+
+    fn extend_trail(&mut self, shift: Shift) {
+        if let Some(trail) = &mut self.trail {
+            trail.push(shift);
+        }
+    }
+
+    fn check_solved(&mut self) {
+        if !self.is_solved() {
+            return;
+        }
+        self.solving.clear();
+        if self.auto_solving {
+            self.stats.pause();
+            self.status = format!(
+                "Solved by the solver after {} moves and {}",
+                self.stats.moves(),
+                self.stats.clock()
+            );
+        } else {
+            self.stats.stop();
+            self.status = format!(
+                "Solved in {} moves and {}",
+                self.stats.moves(),
+                self.stats.clock()
+            );
+        }
+        self.auto_solving = false;
+    }
+
+    /// Takes back the last move, unless the puzzle is solved or being solved.
+    fn undo(&mut self) {
+        if self.is_solved() || !self.solving.is_empty() {
+            return;
+        }
+        if let Some(shift) = self.history.undo() {
+            self.replay(shift);
+            self.status = format!("Undid with {}", shift);
+        }
+    }
+
+    /// Makes the last undone move again, under the same conditions as undoing.
+    fn redo(&mut self) {
+        if self.is_solved() || !self.solving.is_empty() {
+            return;
+        }
+        if let Some(shift) = self.history.redo() {
+            self.replay(shift);
+            self.status = format!("Redid {}", shift);
+        }
+    }
+
+    fn hint(&mut self) {
+        self.status = match solve_for_game(&self.state, self.trail.as_deref()) {
+            Some(solution) => match solution.shifts.first() {
+                Some(shift) => format!(
+                    "Try {} ({} moves left, {} positions searched)",
+                    shift,
+                    solution.shifts.len(),
+                    solution.expanded
+                ),
+                None => "Already solved".to_string(),
+            },
+            None => "No hint available".to_string(),
+        };
+    }
+
+    /// Plays the same solution as the web version would, one move every `SOLVE_STEP`.
+    fn auto_solve(&mut self) {
+        if self.is_solved() {
+            return;
+        }
+        self.status = match solve_for_game(&self.state, self.trail.as_deref()) {
+            Some(solution) if solution.shifts.len() > MAX_AUTO_SOLVE_LENGTH => format!(
+                "Not solving with a solution of {} moves, more than {}",
+                solution.shifts.len(),
+                MAX_AUTO_SOLVE_LENGTH
+            ),
+            Some(solution) => {
+                self.solving = solution.shifts.iter().copied().collect();
+                self.auto_solving = true;
+                self.since_step = 0.0;
+                format!("Solving with {}", format_moves(&solution.shifts))
+            }
+            None => "No solution found within the search budget".to_string(),
+        };
+    }
+
+    /// Advances the timer and any automatic solution by `dt` milliseconds.
+    fn tick(&mut self, dt: f64) {
+        self.stats.tick(dt);
+        if self.solving.is_empty() {
+            return;
+        }
+        self.since_step += dt;
+        if self.since_step >= SOLVE_STEP {
+            self.since_step = 0.0;
+            if let Some(shift) = self.solving.pop_front() {
+                self.replay(shift);
+            }
+        }
+    }
+
+    /// Moves the cursor, or shifts its row or column if Shift is held or the cursor is grabbed.
+    fn arrow(&mut self, direction: Direction, shift_held: bool) {
+        if shift_held || self.cursor.grabbed {
+            self.play(self.cursor.shift_towards(direction));
+        } else {
+            self.cursor.move_towards(&direction, self.state.size());
+        }
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        queue!(
+            out,
+            cursor::MoveTo(0, 0),
+            terminal::Clear(terminal::ClearType::All)
+        )?;
+        let board = text::render(&self.state, Some(&self.cursor), &self.stats);
+        let code = format!("Code {}", code::encode(&self.state));
+        for line in board
+            .lines()
+            .chain(["", &code, &self.status, ""])
+            .chain(CONTROLS.lines())
+        {
+            queue!(out, Print(line), Print("\r\n"))?;
+        }
+        out.flush()
+    }
+}
+
+fn arrow_direction(code: KeyCode) -> Option<Direction> {
+    match code {
+        KeyCode::Up => Some(Direction::North),
+        KeyCode::Down => Some(Direction::South),
+        KeyCode::Left => Some(Direction::West),
+        KeyCode::Right => Some(Direction::East),
+        _ => None,
+    }
+}
+
+/// Handles a key press, returning whether to keep playing.
+fn process_key(game: &mut Game, options: &Options, key: KeyEvent) -> bool {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    if let Some(direction) = arrow_direction(key.code) {
+        game.arrow(direction, key.modifiers.contains(KeyModifiers::SHIFT));
+        return true;
+    }
+    match key.code {
+        KeyCode::Char('c') if ctrl => return false,
+        KeyCode::Char('q') | KeyCode::Esc => return false,
+        KeyCode::Char('z') if ctrl => game.undo(),
+        KeyCode::Char('y') if ctrl => game.redo(),
+        KeyCode::Char('u') => game.undo(),
+        KeyCode::Char('r') => game.redo(),
+        KeyCode::Char('h') => game.hint(),
+        KeyCode::Char('s') => game.auto_solve(),
+        KeyCode::Char('n') => *game = Game::scrambled(options, random_seed()),
+        KeyCode::Enter => game.cursor.grabbed = !game.cursor.grabbed,
+        _ => (),
+    }
+    true
+}
+
+fn run(game: &mut Game, options: &Options, out: &mut impl Write) -> io::Result<()> {
+    let mut last_frame = Instant::now();
+    loop {
+        game.draw(out)?;
+        if event::poll(TICK)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Release && !process_key(game, options, key) {
+                    return Ok(());
+                }
+            }
+        }
+        let now = Instant::now();
+        game.tick(now.duration_since(last_frame).as_secs_f64() * 1000.0);
+        last_frame = now;
+    }
+}
+
+fn main() {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
+        }
+    };
+    let mut game = match &options.code {
+        Some(text) => match code::decode(text) {
+            Some(state) => Game::open(state, format!("Opened position {}", text)),
+            None => {
+                eprintln!("Invalid position code {}", text);
+                process::exit(2);
+            }
+        },
+        None => Game::scrambled(&options, options.seed.unwrap_or_else(random_seed)),
+    };
+
+    let mut out = io::stdout();
+    let result = terminal::enable_raw_mode()
+        .and_then(|_| execute!(out, terminal::EnterAlternateScreen, cursor::Hide))
+        .and_then(|_| run(&mut game, &options, &mut out));
+    // Restore the terminal even if drawing failed
+    let _ = execute!(out, cursor::Show, terminal::LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+
+    match result {
+        Ok(()) => println!("{}", game.status),
+        Err(error) => {
+            eprintln!("Terminal error: {}", error);
+            process::exit(1);
+        }
+    }
+}
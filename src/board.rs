@@ -1,127 +1,352 @@
-use crate::utils::Point;
+use crate::code;
+use crate::cursor::Cursor;
+use crate::puzzle::{Cell, PuzzleState, Shift};
+use crate::renderer::{Rect, Renderer, TextStyle};
+use crate::stats::Stats;
+use crate::theme::{LabelStyle, Theme};
+use crate::utils::{Direction, Point};
 
 pub const BOARD_SIZE: f64 = 1000.0;
 
-const BACKGROUND_COLOUR: &str = "rgb(150,200,255)";
-const TILE_COLOUR: &str = "rgb(40,130,0)";
-const EMPTY_TILE_COLOUR: &str = "rgb(120,0,20)";
-const CORNER_COLOUR: &str = "rgb(20,20,60)";
-const BORDER_ALPHA: f64 = 0.2;
+const TILE_LINE_WIDTH: f64 = 1.0;
+/// The opacity of the labels over the tiles of a picture puzzle
+const PICTURE_LABEL_ALPHA: f64 = 0.35;
+
+const OVERLAY_ALPHA: f64 = 0.6;
+const HUD_CAPTION_SCALE: f64 = 0.15;
+const HUD_VALUE_SCALE: f64 = 0.3;
+const CURSOR_LINE_WIDTH: f64 = 8.0;
+const GRABBED_ALPHA: f64 = 0.2;
+const HINT_ALPHA: f64 = 0.3;
+const HINT_LINE_WIDTH: f64 = 12.0;
 
 pub struct Board {
-    tiles: Vec<Tile>,
-    size: i8,
+    state: PuzzleState,
+    displacement: Option<Displacement>,
+    solved: bool,
+    hint: Option<Shift>,
+    cursor: Option<Cursor>,
+    theme: Theme,
+    /// Whether the numbered tiles show parts of the picture held by the renderer
+    picture: bool,
+}
+
+/// A row or column that is rendered away from its grid position during a shift.
+#[derive(Copy, Clone)]
+enum Displacement {
+    Row(i8, f64),
+    Col(i8, f64),
 }
 
 impl Board {
-    pub fn new(size: i8) -> Board {
-        let mut tiles = Vec::new();
-        let tile_size = BOARD_SIZE / ((size + 2) as f64);
-
-        for x in 1..size + 1 {
-            // Create number tiles
-            for y in 1..size + 1 {
-                let label = format!("{}", (x + size * (y - 1)));
-                let tile = Tile::create(x, y, label, tile_size);
-                tiles.push(tile);
-            }
-            // Create empty tiles
-            tiles.push(Tile::create(x, 0, "".to_string(), tile_size));
-            tiles.push(Tile::create(0, x, "".to_string(), tile_size));
+    pub fn new(size: i8, theme: Theme) -> Board {
+        Board {
+            state: PuzzleState::new(size),
+            displacement: None,
+            solved: true,
+            hint: None,
+            cursor: None,
+            theme,
+            picture: false,
         }
+    }
+
+    /// Builds a board with the arrangement of a position code, see `code::decode`.
+    pub fn from_code(code: &str, theme: Theme) -> Option<Board> {
+        let state = code::decode(code)?;
+        let mut board = Board::new(state.size(), theme);
+        board.set_state(state);
+        Some(board)
+    }
+
+    /// A short, URL safe code for sharing the current arrangement of the tiles.
+    pub fn to_code(&self) -> String {
+        code::encode(&self.state)
+    }
+
+    pub fn state(&self) -> &PuzzleState {
+        &self.state
+    }
+
+    /// Replaces the arrangement of the tiles, e.g. when resuming a saved game.
+    pub fn set_state(&mut self, state: PuzzleState) {
+        self.solved = state.is_solved();
+        self.state = state;
+        self.displacement = None;
+        self.hint = None;
+    }
 
-        Board { tiles, size }
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
     }
 
-    pub fn render(&self, ctx: &web_sys::CanvasRenderingContext2d) {
+    /// Shows parts of the renderer's picture on the numbered tiles, with faint labels unless the
+    /// theme hides them.
+    pub fn set_picture(&mut self, picture: bool) {
+        self.picture = picture;
+    }
+
+    pub fn hint(&self) -> Option<Shift> {
+        self.hint
+    }
+
+    /// Highlights the shift until it is cleared or the next shift completes.
+    pub fn set_hint(&mut self, hint: Option<Shift>) {
+        self.hint = hint;
+    }
+
+    pub fn cursor(&self) -> Option<Cursor> {
+        self.cursor
+    }
+
+    pub fn set_cursor(&mut self, cursor: Option<Cursor>) {
+        self.cursor = cursor;
+    }
+
+    /// Whether the last completed shift left the tiles in their original order.
+    pub fn is_solved(&self) -> bool {
+        self.solved
+    }
+
+    pub fn render(&self, renderer: &mut dyn Renderer, stats: &Stats) {
         let tile_size = self.tile_size();
-        ctx.set_fill_style(&BACKGROUND_COLOUR.into());
-        ctx.fill_rect(0.0, 0.0, BOARD_SIZE, BOARD_SIZE);
+        renderer.fill_rect(
+            Rect::new(0.0, 0.0, BOARD_SIZE, BOARD_SIZE),
+            &self.theme.background,
+        );
 
-        for tile in &self.tiles {
-            tile.render(ctx, tile_size);
+        for tile in self.tiles() {
+            tile.render(renderer, self, tile_size);
         }
 
         // Add shading to the border tiles
-        ctx.set_fill_style(&"rgb(0,0,0".into());
-        let current_alpha = ctx.global_alpha();
-        ctx.set_global_alpha(BORDER_ALPHA);
-        ctx.fill_rect(tile_size, 0.0, BOARD_SIZE - tile_size * 2.0, tile_size); // top
-        ctx.fill_rect(0.0, tile_size, tile_size, BOARD_SIZE - tile_size * 2.0); // left
-        ctx.fill_rect(
-            BOARD_SIZE - tile_size,
-            tile_size,
-            tile_size,
-            BOARD_SIZE - tile_size * 2.0,
-        ); // right
-        ctx.fill_rect(
-            tile_size,
-            BOARD_SIZE - tile_size,
-            BOARD_SIZE - 2.0 * tile_size,
-            tile_size,
-        ); // bottom
-        ctx.set_global_alpha(current_alpha);
+        renderer.set_alpha(self.theme.border_alpha);
+        let inner = BOARD_SIZE - 2.0 * tile_size;
+        let far = BOARD_SIZE - tile_size;
+        for rect in [
+            Rect::new(tile_size, 0.0, inner, tile_size), // top
+            Rect::new(0.0, tile_size, tile_size, inner), // left
+            Rect::new(far, tile_size, tile_size, inner), // right
+            Rect::new(tile_size, far, inner, tile_size), // bottom
+        ] {
+            renderer.fill_rect(rect, &self.theme.border_shade);
+        }
+        renderer.set_alpha(1.0);
 
         // Add corners
-        ctx.set_fill_style(&CORNER_COLOUR.into());
-        ctx.fill_rect(0.0, 0.0, tile_size, tile_size);
-        ctx.fill_rect(0.0, BOARD_SIZE - tile_size, tile_size, tile_size);
-        ctx.fill_rect(BOARD_SIZE - tile_size, 0.0, tile_size, tile_size);
-        ctx.fill_rect(
-            BOARD_SIZE - tile_size,
-            BOARD_SIZE - tile_size,
-            tile_size,
-            tile_size,
+        for (x, y) in [(0.0, 0.0), (0.0, far), (far, 0.0), (far, far)] {
+            renderer.fill_rect(Rect::new(x, y, tile_size, tile_size), &self.theme.corner);
+        }
+        self.render_hud(renderer, stats);
+
+        if let Some(cursor) = &self.cursor {
+            self.render_cursor(renderer, cursor);
+        }
+        if let Some(hint) = &self.hint {
+            self.render_hint(renderer, hint);
+        }
+    }
+
+    /// Shows the move count and the timer in the top corners, which tiles never occupy.
+    fn render_hud(&self, renderer: &mut dyn Renderer, stats: &Stats) {
+        let tile_size = self.tile_size();
+        let caption = TextStyle {
+            size: HUD_CAPTION_SCALE * tile_size,
+            colour: &self.theme.hud,
+            family: &self.theme.font_family,
+            outline: false,
+        };
+        let value = TextStyle {
+            size: HUD_VALUE_SCALE * tile_size,
+            ..caption
+        };
+        let fields = [
+            ("MOVES", format!("{}", stats.moves()), 0.5 * tile_size),
+            ("TIME", stats.clock(), BOARD_SIZE - 0.5 * tile_size),
+        ];
+
+        for (caption_text, value_text, x) in fields.iter() {
+            renderer.text(
+                caption_text,
+                Point {
+                    x: *x,
+                    y: 0.25 * tile_size,
+                },
+                &caption,
+            );
+            renderer.text(
+                value_text,
+                Point {
+                    x: *x,
+                    y: 0.6 * tile_size,
+                },
+                &value,
+            );
+        }
+    }
+
+    fn render_cursor(&self, renderer: &mut dyn Renderer, cursor: &Cursor) {
+        let tile_size = self.tile_size();
+        let x = cursor.position.x as f64 * tile_size;
+        let y = cursor.position.y as f64 * tile_size;
+
+        if cursor.grabbed {
+            // Highlight both the row and the column that the arrow keys shift
+            renderer.set_alpha(GRABBED_ALPHA);
+            renderer.fill_rect(Rect::new(0.0, y, BOARD_SIZE, tile_size), &self.theme.cursor);
+            renderer.fill_rect(Rect::new(x, 0.0, tile_size, BOARD_SIZE), &self.theme.cursor);
+            renderer.set_alpha(1.0);
+        }
+        renderer.stroke_rect(
+            Rect::new(x, y, tile_size, tile_size),
+            &self.theme.cursor,
+            CURSOR_LINE_WIDTH,
         );
     }
 
-    pub fn shift(&mut self, from: &Point<f64>, to: &Point<f64>, end: bool) {
+    fn render_hint(&self, renderer: &mut dyn Renderer, hint: &Shift) {
         let tile_size = self.tile_size();
-        let shift_vector = self.get_shift_vector(from, to);
-        let row = (from.y / tile_size) as i8;
-        let col = (from.x / tile_size) as i8;
+        let offset = (hint.idx as f64) * tile_size;
+        let middle = offset + 0.5 * tile_size;
+        let (start, end) = match hint.direction {
+            Direction::East => (0.5 * tile_size, BOARD_SIZE - 0.5 * tile_size),
+            Direction::West => (BOARD_SIZE - 0.5 * tile_size, 0.5 * tile_size),
+            Direction::South => (0.5 * tile_size, BOARD_SIZE - 0.5 * tile_size),
+            Direction::North => (BOARD_SIZE - 0.5 * tile_size, 0.5 * tile_size),
+        };
+        let head = 0.25 * tile_size * (end - start).signum();
 
-        // horizontal shift
-        if shift_vector.x.abs() > shift_vector.y.abs() {
-            let grid_distance: i8;
-            let distance: f64;
-            if end {
-                grid_distance = self.grid_distance(shift_vector.x);
-                distance = grid_distance as f64 * tile_size;
-            } else {
-                grid_distance = 0;
-                distance = shift_vector.x.min(tile_size).max(-tile_size); // clamp on f64 is unstable
-            };
-            for t in self.get_row(row) {
-                t.render_position.x = t.grid_position.x as f64 * tile_size + distance;
-                t.grid_position.x += grid_distance;
-            }
-            for t in self.get_col(col) {
-                t.render_position.y = t.grid_position.y as f64 * tile_size;
-            }
+        // Highlight the line to shift
+        renderer.set_alpha(HINT_ALPHA);
+        if hint.is_row() {
+            renderer.fill_rect(
+                Rect::new(0.0, offset, BOARD_SIZE, tile_size),
+                &self.theme.hint,
+            );
+        } else {
+            renderer.fill_rect(
+                Rect::new(offset, 0.0, tile_size, BOARD_SIZE),
+                &self.theme.hint,
+            );
         }
-        // vertical shift
-        else {
-            let grid_distance: i8;
-            let distance: f64;
-            if end {
-                grid_distance = self.grid_distance(shift_vector.y);
-                distance = grid_distance as f64 * tile_size;
+        renderer.set_alpha(1.0);
+
+        // Draw an arrow along it in the direction of the shift, with `along` and `across` the
+        // coordinates along and across the line
+        let point = |along: f64, across: f64| {
+            if hint.is_row() {
+                Point {
+                    x: along,
+                    y: across,
+                }
             } else {
-                grid_distance = 0;
-                distance = shift_vector.y.min(tile_size).max(-tile_size); // clamp on f64 is unstable
-            };
-            for t in self.get_row(row) {
-                t.render_position.x = t.grid_position.x as f64 * tile_size;
-            }
-            for t in self.get_col(col) {
-                t.render_position.y = t.grid_position.y as f64 * tile_size + distance;
-                t.grid_position.y += grid_distance;
+                Point {
+                    x: across,
+                    y: along,
+                }
             }
+        };
+        renderer.polyline(
+            &[point(start, middle), point(end, middle)],
+            &self.theme.hint,
+            HINT_LINE_WIDTH,
+        );
+        renderer.polyline(
+            &[
+                point(end - head, middle - head.abs()),
+                point(end, middle),
+                point(end - head, middle + head.abs()),
+            ],
+            &self.theme.hint,
+            HINT_LINE_WIDTH,
+        );
+    }
+
+    pub fn render_solved_overlay(&self, renderer: &mut dyn Renderer) {
+        renderer.set_alpha(OVERLAY_ALPHA);
+        renderer.fill_rect(
+            Rect::new(0.0, 0.0, BOARD_SIZE, BOARD_SIZE),
+            &self.theme.overlay,
+        );
+        renderer.set_alpha(1.0);
+
+        renderer.text(
+            "Solved!",
+            Point {
+                x: BOARD_SIZE / 2.0,
+                y: BOARD_SIZE / 2.0,
+            },
+            &TextStyle {
+                size: self.theme.label_scale * self.tile_size(),
+                colour: &self.theme.overlay_text,
+                family: &self.theme.font_family,
+                outline: false,
+            },
+        );
+    }
+
+    /// Displaces the row or column under `from` towards `to`, by at most a tile and only
+    /// the way it can be shifted. Shifts are completed with `apply_shift`.
+    pub fn drag(&mut self, from: &Point<f64>, to: &Point<f64>) {
+        let tile_size = self.tile_size();
+        let shift_vector = self.get_shift_vector(from, to);
+
+        // clamp on f64 is unstable
+        self.displacement = Some(if shift_vector.x.abs() > shift_vector.y.abs() {
+            let row = (from.y / tile_size) as i8;
+            Displacement::Row(row, shift_vector.x.min(tile_size).max(-tile_size))
+        } else {
+            let col = (from.x / tile_size) as i8;
+            Displacement::Col(col, shift_vector.y.min(tile_size).max(-tile_size))
+        });
+    }
+
+    /// Shows the line of the shift displaced by `distance` along it, e.g. while animating it.
+    pub fn displace(&mut self, shift: &Shift, distance: f64) {
+        let idx = shift.idx as i8;
+        self.displacement = Some(match shift.direction {
+            Direction::East => Displacement::Row(idx, distance),
+            Direction::West => Displacement::Row(idx, -distance),
+            Direction::South => Displacement::Col(idx, distance),
+            Direction::North => Displacement::Col(idx, -distance),
+        });
+    }
+
+    /// The shift the displaced row or column is heading for, and how far along it is in
+    /// fractions of a tile.
+    pub fn displacement(&self) -> Option<(Shift, f64)> {
+        let (idx, distance, direction) = match self.displacement? {
+            Displacement::Row(idx, distance) if distance > 0.0 => (idx, distance, Direction::East),
+            Displacement::Row(idx, distance) if distance < 0.0 => (idx, distance, Direction::West),
+            Displacement::Col(idx, distance) if distance > 0.0 => (idx, distance, Direction::South),
+            Displacement::Col(idx, distance) if distance < 0.0 => (idx, distance, Direction::North),
+            _ => return None,
+        };
+        let shift = Shift {
+            idx: idx as u8,
+            direction,
+        };
+        Some((shift, distance.abs() / self.tile_size()))
+    }
+
+    /// Applies the shift at once if it is legal, putting any displaced line back.
+    pub fn apply_shift(&mut self, shift: &Shift) -> bool {
+        self.displacement = None;
+        if !self.state.apply(shift) {
+            return false;
         }
+
+        self.hint = None;
+        self.solved = self.state.is_solved();
+        true
     }
 
-    fn get_shift_vector(&mut self, from: &Point<f64>, to: &Point<f64>) -> Point<f64> {
+    /// Puts a displaced row or column back without shifting it.
+    pub fn cancel_shift(&mut self) {
+        self.displacement = None;
+    }
+
+    fn get_shift_vector(&self, from: &Point<f64>, to: &Point<f64>) -> Point<f64> {
         let tile_size = self.tile_size();
         let mut v = Point {
             x: to.x - from.x,
@@ -130,111 +355,107 @@ impl Board {
 
         // Figure out which way we cannot move horizontally, and update the vector accordingly
         let row = (from.y / tile_size) as i8;
-        if row > 0 && row < self.size + 1 {
-            if self.get_row(row).iter().any(|t| t.grid_position.x == 0) {
-                v.x = v.x.max(0.0); // If there is a tile in the left column, we cannot move left
-            } else {
-                v.x = v.x.min(0.0); // Otherwise, there must be a tile in the right column and we cannot move right
-            }
-        } else {
-            // If we are in the first or last row, we cannot move horizontally at all
-            v.x = 0.0;
+        match self.state.row_shift(row).map(|s| s.direction) {
+            Some(Direction::East) => v.x = v.x.max(0.0), // If there is a tile in the left column, we cannot move left
+            Some(_) => v.x = v.x.min(0.0), // Otherwise, there must be a tile in the right column and we cannot move right
+            None => v.x = 0.0, // If we are in the first or last row, we cannot move horizontally at all
         }
 
         // Figure out which way we cannot move vertically, and update the vector accordingly
         let col = (from.x / tile_size) as i8;
-        if col > 0 && col < self.size + 1 {
-            if self.get_col(col).iter().any(|t| t.grid_position.y == 0) {
-                v.y = v.y.max(0.0); // If there is a tile in the top column, we cannot move up
-            } else {
-                v.y = v.y.min(0.0); // Otherwise, there must be a tile in the bottom column and we cannot move down
-            }
-        } else {
-            // If we are in the first or last column, we cannot move vertically at all
-            v.y = 0.0;
+        match self.state.col_shift(col).map(|s| s.direction) {
+            Some(Direction::South) => v.y = v.y.max(0.0), // If there is a tile in the top row, we cannot move up
+            Some(_) => v.y = v.y.min(0.0), // Otherwise, there must be a tile in the bottom row and we cannot move down
+            None => v.y = 0.0, // If we are in the first or last column, we cannot move vertically at all
         }
 
         v
     }
 
-    fn grid_distance(&self, distance: f64) -> i8 {
-        let threshold = self.tile_size() / 2.0;
-        if distance > threshold {
-            1
-        } else {
-            if distance < -threshold {
-                -1
-            } else {
-                0
-            }
-        }
-    }
-
     pub fn tile_size(&self) -> f64 {
-        BOARD_SIZE / ((self.size + 2) as f64)
+        BOARD_SIZE / ((self.state.size() + 2) as f64)
     }
 
-    fn get_row(&mut self, row: i8) -> Vec<&mut Tile> {
-        self.tiles
-            .iter_mut()
-            .filter(|t| t.grid_position.y == row)
-            .collect()
-    }
-
-    fn get_col(&mut self, col: i8) -> Vec<&mut Tile> {
-        self.tiles
-            .iter_mut()
-            .filter(|t| t.grid_position.x == col)
+    fn tiles(&self) -> Vec<Tile> {
+        let tile_size = self.tile_size();
+        self.state
+            .tiles()
+            .map(|(position, cell)| {
+                let mut render_position = Point {
+                    x: position.x as f64 * tile_size,
+                    y: position.y as f64 * tile_size,
+                };
+                match self.displacement {
+                    Some(Displacement::Row(row, distance)) if position.y == row => {
+                        render_position.x += distance
+                    }
+                    Some(Displacement::Col(col, distance)) if position.x == col => {
+                        render_position.y += distance
+                    }
+                    _ => (),
+                }
+                Tile {
+                    render_position,
+                    cell,
+                }
+            })
             .collect()
     }
 }
 
-pub struct Tile {
-    grid_position: Point<i8>,
+struct Tile {
     render_position: Point<f64>,
-    label: String,
-}
-
-impl std::fmt::Debug for Tile {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Tile({}, {}, label={})",
-            self.grid_position.x, self.grid_position.y, &self.label
-        )
-    }
+    cell: Cell,
 }
 
 impl Tile {
-    fn create(x: i8, y: i8, label: String, tile_size: f64) -> Tile {
-        let render_position = Point {
-            x: x as f64 * tile_size,
-            y: y as f64 * tile_size,
+    fn render(&self, renderer: &mut dyn Renderer, board: &Board, size: f64) {
+        let theme = &board.theme;
+        let (class, fill) = match self.cell {
+            Cell::Number(_) => ("tile", &theme.tile),
+            _ => ("tile empty", &theme.empty_tile),
         };
-        Tile {
-            grid_position: Point { x, y },
-            render_position,
-            label,
+        // Tiles are drawn relative to their position, so that shifts only move the group
+        renderer.begin_group(class, self.render_position);
+        let rect = Rect::new(0.0, 0.0, size, size).rounded(theme.tile_radius * size);
+        renderer.fill_rect(rect, fill);
+        if let (true, Cell::Number(number)) = (board.picture, self.cell) {
+            // Every tile shows the part of the picture where it belongs
+            let columns = board.state.size() as usize;
+            let part = 1.0 / columns as f64;
+            let home = number as usize - 1;
+            let source = Rect::new(
+                (home % columns) as f64 * part,
+                (home / columns) as f64 * part,
+                part,
+                part,
+            );
+            renderer.picture(source, rect);
         }
-    }
-
-    fn render(&self, ctx: &web_sys::CanvasRenderingContext2d, size: f64) {
-        let x = self.render_position.x;
-        let y = self.render_position.y;
+        renderer.stroke_rect(rect, &theme.label, TILE_LINE_WIDTH);
 
-        if self.label.is_empty() {
-            ctx.set_fill_style(&EMPTY_TILE_COLOUR.into());
-        } else {
-            ctx.set_fill_style(&TILE_COLOUR.into());
+        let label = self.cell.label();
+        if !label.is_empty() && theme.label_style != LabelStyle::Hidden {
+            if board.picture {
+                renderer.set_alpha(PICTURE_LABEL_ALPHA);
+            }
+            renderer.text(
+                &label,
+                Point {
+                    x: 0.5 * size,
+                    y: 0.5 * size,
+                },
+                &TextStyle {
+                    size: theme.label_scale * size,
+                    colour: &theme.label,
+                    family: &theme.font_family,
+                    outline: theme.label_style == LabelStyle::Outlined,
+                },
+            );
+            if board.picture {
+                renderer.set_alpha(1.0);
+            }
         }
-        ctx.fill_rect(x, y, size, size);
-        ctx.stroke_rect(x, y, size, size);
-
-        ctx.set_stroke_style(&"rgb(255,255,255)".into());
-        ctx.set_fill_style(&"rgb(255,255,255)".into());
-        ctx.fill_text(&self.label, x + 0.5 * size, y + 0.5 * size)
-            .unwrap();
-        ctx.stroke_text(&self.label, x + 0.5 * size, y + 0.5 * size)
-            .unwrap();
+        renderer.end_group();
     }
 }
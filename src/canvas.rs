@@ -0,0 +1,163 @@
+use crate::renderer::{Rect, Renderer, TextStyle, Transform};
+use crate::utils::Point;
+
+const TEXT_OUTLINE_WIDTH: f64 = 1.0;
+
+/// An image to cut the tiles of a picture puzzle from.
+pub enum Picture {
+    Image(web_sys::HtmlImageElement),
+    Bitmap(web_sys::ImageBitmap),
+}
+
+impl Picture {
+    /// The width and height in pixels, which are zero until an image has loaded.
+    fn size(&self) -> (f64, f64) {
+        match self {
+            Picture::Image(image) => (image.natural_width() as f64, image.natural_height() as f64),
+            Picture::Bitmap(bitmap) => (bitmap.width() as f64, bitmap.height() as f64),
+        }
+    }
+}
+
+/// Renders onto a canvas through its 2D context.
+pub struct CanvasRenderer {
+    ctx: web_sys::CanvasRenderingContext2d,
+    picture: Option<Picture>,
+}
+
+impl CanvasRenderer {
+    pub fn new(ctx: web_sys::CanvasRenderingContext2d) -> CanvasRenderer {
+        CanvasRenderer { ctx, picture: None }
+    }
+
+    pub fn set_picture(&mut self, picture: Option<Picture>) {
+        self.picture = picture;
+    }
+
+    pub fn canvas(&self) -> web_sys::HtmlCanvasElement {
+        self.ctx.canvas().unwrap()
+    }
+
+    fn rounded_rect_path(&self, rect: Rect) {
+        let (left, top) = (rect.x, rect.y);
+        let (right, bottom) = (rect.x + rect.width, rect.y + rect.height);
+        let ctx = &self.ctx;
+        ctx.begin_path();
+        ctx.move_to(left + rect.radius, top);
+        ctx.arc_to(right, top, right, bottom, rect.radius).unwrap();
+        ctx.arc_to(right, bottom, left, bottom, rect.radius)
+            .unwrap();
+        ctx.arc_to(left, bottom, left, top, rect.radius).unwrap();
+        ctx.arc_to(left, top, right, top, rect.radius).unwrap();
+        ctx.close_path();
+    }
+}
+
+impl Renderer for CanvasRenderer {
+    fn set_transform(&mut self, transform: Transform) {
+        let Transform { scale, offset } = transform;
+        self.ctx
+            .set_transform(scale.x, 0.0, 0.0, scale.y, offset.x, offset.y)
+            .unwrap();
+    }
+
+    fn set_alpha(&mut self, alpha: f64) {
+        self.ctx.set_global_alpha(alpha);
+    }
+
+    fn fill_rect(&mut self, rect: Rect, colour: &str) {
+        self.ctx.set_fill_style_str(colour);
+        if rect.radius > 0.0 {
+            self.rounded_rect_path(rect);
+            self.ctx.fill();
+        } else {
+            self.ctx.fill_rect(rect.x, rect.y, rect.width, rect.height);
+        }
+    }
+
+    fn stroke_rect(&mut self, rect: Rect, colour: &str, line_width: f64) {
+        self.ctx.set_stroke_style_str(colour);
+        self.ctx.set_line_width(line_width);
+        if rect.radius > 0.0 {
+            self.rounded_rect_path(rect);
+            self.ctx.stroke();
+        } else {
+            self.ctx
+                .stroke_rect(rect.x, rect.y, rect.width, rect.height);
+        }
+    }
+
+    fn text(&mut self, text: &str, centre: Point<f64>, style: &TextStyle) {
+        // Resizing the canvas resets its whole state, alignment included
+        self.ctx.set_text_align("center");
+        self.ctx.set_text_baseline("middle");
+        self.ctx
+            .set_font(&format!("{}px {}", style.size, style.family));
+        self.ctx.set_fill_style_str(style.colour);
+        self.ctx.fill_text(text, centre.x, centre.y).unwrap();
+        if style.outline {
+            self.ctx.set_stroke_style_str(style.colour);
+            self.ctx.set_line_width(TEXT_OUTLINE_WIDTH);
+            self.ctx.stroke_text(text, centre.x, centre.y).unwrap();
+        }
+    }
+
+    fn polyline(&mut self, points: &[Point<f64>], colour: &str, line_width: f64) {
+        self.ctx.set_stroke_style_str(colour);
+        self.ctx.set_line_width(line_width);
+        self.ctx.begin_path();
+        for (i, point) in points.iter().enumerate() {
+            if i == 0 {
+                self.ctx.move_to(point.x, point.y);
+            } else {
+                self.ctx.line_to(point.x, point.y);
+            }
+        }
+        self.ctx.stroke();
+    }
+
+    fn picture(&mut self, source: Rect, dest: Rect) {
+        let picture = match &self.picture {
+            Some(picture) => picture,
+            None => return,
+        };
+        let (width, height) = picture.size();
+        let side = width.min(height);
+        if side == 0.0 {
+            return;
+        }
+        let sx = 0.5 * (width - side) + source.x * side;
+        let sy = 0.5 * (height - side) + source.y * side;
+        let (sw, sh) = (source.width * side, source.height * side);
+
+        self.ctx.save();
+        if dest.radius > 0.0 {
+            self.rounded_rect_path(dest);
+            self.ctx.clip();
+        }
+        let (dx, dy, dw, dh) = (dest.x, dest.y, dest.width, dest.height);
+        match picture {
+            Picture::Image(image) => self
+                .ctx
+                .draw_image_with_html_image_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+                    image, sx, sy, sw, sh, dx, dy, dw, dh,
+                ),
+            Picture::Bitmap(bitmap) => self
+                .ctx
+                .draw_image_with_image_bitmap_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+                    bitmap, sx, sy, sw, sh, dx, dy, dw, dh,
+                ),
+        }
+        .unwrap();
+        self.ctx.restore();
+    }
+
+    fn begin_group(&mut self, _class: &str, offset: Point<f64>) {
+        self.ctx.save();
+        self.ctx.translate(offset.x, offset.y).unwrap();
+    }
+
+    fn end_group(&mut self) {
+        self.ctx.restore();
+    }
+}
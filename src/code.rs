@@ -0,0 +1,154 @@
+//! Compact, URL safe codes for sharing the exact arrangement of a board.
+//!
+//! The first character holds the size of the board. The remaining characters pack a
+//! bit stream six bits at a time: one bit for every row telling whether its border tile
+//! is on the east side, one bit for every column telling whether its border tile is on
+//! the south side, and then the content of every occupied slot in row major order,
+//! `0` for an empty tile and `n` for the tile numbered `n`.
+use crate::config::{MAX_SIZE, MIN_SIZE};
+use crate::puzzle::{Cell, PuzzleState};
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encodes the state as a short string of letters, digits, `-` and `_`.
+pub fn encode(state: &PuzzleState) -> String {
+    let size = state.size();
+    let last = size + 1;
+    let mut bits = BitWriter::default();
+    for i in 1..last {
+        bits.push(state.get(last, i) != Cell::Vacant);
+    }
+    for i in 1..last {
+        bits.push(state.get(i, last) != Cell::Vacant);
+    }
+
+    let width = value_width(size);
+    for (_, cell) in state.tiles() {
+        let value = match cell {
+            Cell::Number(n) => n as u32,
+            _ => 0,
+        };
+        bits.push_value(value, width);
+    }
+
+    let mut code = String::with_capacity(bits.chars.len() + 1);
+    code.push(ALPHABET[size as usize] as char);
+    code.extend(
+        bits.finish()
+            .into_iter()
+            .map(|c| ALPHABET[c as usize] as char),
+    );
+    code
+}
+
+/// Decodes a code made by `encode`, returning `None` unless it describes a valid arrangement
+/// of a size the game can be played at.
+pub fn decode(code: &str) -> Option<PuzzleState> {
+    let mut values = code.bytes().map(|c| ALPHABET.iter().position(|a| *a == c));
+    let size = values.next()?? as i8;
+    if !(MIN_SIZE..=MAX_SIZE).contains(&size) {
+        return None;
+    }
+    let chars = values.collect::<Option<Vec<usize>>>()?;
+    let mut bits = BitReader::new(&chars);
+
+    let last = size + 1;
+    let width = (last + 1) as usize;
+    let mut occupied = vec![false; width * width];
+    for y in 1..last {
+        let east = bits.read(1)? == 1;
+        occupied[y as usize * width + if east { last as usize } else { 0 }] = true;
+        for x in 1..last {
+            occupied[y as usize * width + x as usize] = true;
+        }
+    }
+    for x in 1..last {
+        let south = bits.read(1)? == 1;
+        occupied[if south { last as usize } else { 0 } * width + x as usize] = true;
+    }
+
+    let value_width = value_width(size);
+    let mut cells = Vec::with_capacity(width * width);
+    for slot in occupied {
+        cells.push(if !slot {
+            Cell::Vacant
+        } else {
+            match bits.read(value_width)? {
+                0 => Cell::Empty,
+                n => Cell::Number(n as u8),
+            }
+        });
+    }
+
+    if !bits.is_exhausted() {
+        return None;
+    }
+    PuzzleState::from_cells(size, cells)
+}
+
+/// The number of bits needed for every value from `0` to `size * size`.
+fn value_width(size: i8) -> u32 {
+    let max = (size as u32) * (size as u32);
+    u32::BITS - max.leading_zeros()
+}
+
+#[derive(Default)]
+struct BitWriter {
+    chars: Vec<u8>,
+    current: u8,
+    used: u32,
+}
+
+impl BitWriter {
+    fn push(&mut self, bit: bool) {
+        self.current = (self.current << 1) | bit as u8;
+        self.used += 1;
+        if self.used == 6 {
+            self.chars.push(self.current);
+            self.current = 0;
+            self.used = 0;
+        }
+    }
+
+    fn push_value(&mut self, value: u32, width: u32) {
+        for i in (0..width).rev() {
+            self.push((value >> i) & 1 == 1);
+        }
+    }
+
+    /// Pads the last character with zeros.
+    fn finish(mut self) -> Vec<u8> {
+        if self.used > 0 {
+            self.chars.push(self.current << (6 - self.used));
+        }
+        self.chars
+    }
+}
+
+struct BitReader<'a> {
+    chars: &'a [usize],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(chars: &'a [usize]) -> BitReader<'a> {
+        BitReader { chars, position: 0 }
+    }
+
+    fn read(&mut self, width: u32) -> Option<u32> {
+        let mut value = 0;
+        for _ in 0..width {
+            let c = self.chars.get(self.position / 6)?;
+            let bit = (c >> (5 - self.position % 6)) & 1;
+            value = (value << 1) | bit as u32;
+            self.position += 1;
+        }
+        Some(value)
+    }
+
+    /// Whether only the zero padding of the last character is left.
+    fn is_exhausted(&self) -> bool {
+        let rest = self.chars.len() * 6 - self.position;
+        rest < 6 && self.chars.last().is_none_or(|c| c & ((1 << rest) - 1) == 0)
+    }
+}
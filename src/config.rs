@@ -0,0 +1,178 @@
+use crate::animation::Easing;
+use crate::shuffle::Difficulty;
+use crate::theme::Theme;
+use crate::utils::log;
+use std::fmt::Write;
+use wasm_bindgen::prelude::*;
+
+/// The smallest number of rows and columns of numbered tiles a game can have.
+pub const MIN_SIZE: i8 = 2;
+/// The largest number of rows and columns of numbered tiles a game can have.
+pub const MAX_SIZE: i8 = 9;
+/// The fewest random shifts a game is shuffled with.
+pub const MIN_SHUFFLE_STEPS: i8 = 1;
+/// The most random shifts a shuffle can be asked for.
+pub const MAX_SHUFFLE_STEPS: i8 = i8::MAX;
+const SAVE_KEY_PREFIX: &str = "wasm-puzzle";
+
+/// Options for setting up a game, configured from JavaScript through property setters.
+#[wasm_bindgen]
+pub struct GameConfig {
+    pub(crate) size: i8,
+    pub(crate) shuffle_steps: i8,
+    pub(crate) canvas: Option<web_sys::HtmlCanvasElement>,
+    pub(crate) canvas_id: String,
+    pub(crate) svg_container: Option<web_sys::HtmlElement>,
+    pub(crate) animation_duration: f64,
+    pub(crate) release_duration: f64,
+    pub(crate) easing: Easing,
+    pub(crate) seed: Option<u32>,
+    pub(crate) difficulty: Option<Difficulty>,
+    pub(crate) theme: Theme,
+    pub(crate) persist: bool,
+    pub(crate) code: Option<String>,
+    pub(crate) url_code: bool,
+}
+
+impl Default for GameConfig {
+    fn default() -> GameConfig {
+        GameConfig {
+            size: 3,
+            shuffle_steps: 10,
+            canvas: None,
+            canvas_id: "puzzle-canvas".to_string(),
+            svg_container: None,
+            animation_duration: 500.0,
+            release_duration: 150.0,
+            easing: Easing::default(),
+            seed: None,
+            difficulty: None,
+            theme: Theme::default(),
+            persist: true,
+            code: None,
+            url_code: true,
+        }
+    }
+}
+
+impl GameConfig {
+    /// The local storage key of the saved game, separate for every board size and way of
+    /// scrambling, so that different games on different pages do not overwrite each other.
+    pub fn save_key(&self, size: i8) -> Option<String> {
+        if !self.persist {
+            return None;
+        }
+        let scramble = match self.difficulty {
+            Some(difficulty) => difficulty.to_string(),
+            None => format!("steps{}", self.shuffle_steps),
+        };
+        let mut key = format!("{}-{}-{}", SAVE_KEY_PREFIX, size, scramble);
+        if let Some(seed) = self.seed {
+            write!(key, "-seed{}", seed).unwrap();
+        }
+        Some(key)
+    }
+}
+
+#[wasm_bindgen]
+impl GameConfig {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> GameConfig {
+        GameConfig::default()
+    }
+
+    /// The number of rows and columns of numbered tiles, clamped to a playable range.
+    #[wasm_bindgen(setter)]
+    pub fn set_size(&mut self, size: i8) {
+        self.size = size.clamp(MIN_SIZE, MAX_SIZE);
+    }
+
+    /// The number of random shifts, at least one, ignored when a difficulty is set.
+    #[wasm_bindgen(setter)]
+    pub fn set_shuffle_steps(&mut self, steps: i8) {
+        self.shuffle_steps = steps.clamp(MIN_SHUFFLE_STEPS, MAX_SHUFFLE_STEPS);
+    }
+
+    /// The canvas to draw on, taking precedence over `canvas_id`.
+    #[wasm_bindgen(setter)]
+    pub fn set_canvas(&mut self, canvas: Option<web_sys::HtmlCanvasElement>) {
+        self.canvas = canvas;
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_canvas_id(&mut self, canvas_id: String) {
+        self.canvas_id = canvas_id;
+    }
+
+    /// An element to render the board into as SVG instead of onto the canvas.
+    #[wasm_bindgen(setter)]
+    pub fn set_svg_container(&mut self, container: Option<web_sys::HtmlElement>) {
+        self.svg_container = container;
+    }
+
+    /// How long a single animated shift takes, in milliseconds.
+    #[wasm_bindgen(setter)]
+    pub fn set_animation_duration(&mut self, duration: f64) {
+        self.animation_duration = duration.max(0.0);
+    }
+
+    /// How long a released row or column takes to move into place, in milliseconds.
+    #[wasm_bindgen(setter)]
+    pub fn set_release_duration(&mut self, duration: f64) {
+        self.release_duration = duration.max(0.0);
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_easing(&mut self, easing: Easing) {
+        self.easing = easing;
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_seed(&mut self, seed: Option<u32>) {
+        self.seed = seed;
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_difficulty(&mut self, difficulty: Option<Difficulty>) {
+        self.difficulty = difficulty;
+    }
+
+    /// Whether to save the game to local storage after every shift, and resume it on setup.
+    #[wasm_bindgen(setter)]
+    pub fn set_persist(&mut self, persist: bool) {
+        self.persist = persist;
+    }
+
+    /// A position code to start from instead of shuffling, which also sets the size.
+    /// Without one, a code in the URL fragment is used if `url_code` is set.
+    #[wasm_bindgen(setter)]
+    pub fn set_code(&mut self, code: Option<String>) {
+        self.code = code;
+    }
+
+    /// Whether to open a position code in the URL fragment, on by default so that shared
+    /// links open their position. Fragments that are not codes, such as page anchors, are
+    /// ignored; turn it off for games that should never follow the URL.
+    #[wasm_bindgen(setter)]
+    pub fn set_url_code(&mut self, url_code: bool) {
+        self.url_code = url_code;
+    }
+
+    /// The name of a built-in theme; unknown names keep the current theme.
+    #[wasm_bindgen(setter)]
+    pub fn set_theme(&mut self, name: &str) {
+        match Theme::by_name(name) {
+            Some(theme) => self.theme = theme,
+            None => log(&format!("Unknown theme {}", name)),
+        }
+    }
+
+    /// A theme given as JSON, see `theme::Theme`; unreadable themes keep the current theme.
+    #[wasm_bindgen(setter)]
+    pub fn set_custom_theme(&mut self, json: &str) {
+        match Theme::from_json(json) {
+            Ok(theme) => self.theme = theme,
+            Err(e) => log(&format!("Ignoring unreadable theme: {}", e)),
+        }
+    }
+}
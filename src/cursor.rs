@@ -0,0 +1,56 @@
+use crate::puzzle::Shift;
+use crate::utils::{Direction, Point};
+
+/// The keyboard focus, selecting the row and column of a numbered tile slot.
+#[derive(Copy, Clone, Debug)]
+pub struct Cursor {
+    pub position: Point<i8>,
+    /// While grabbed, the arrow keys shift the selected row or column instead of moving the cursor
+    pub grabbed: bool,
+}
+
+impl Default for Cursor {
+    fn default() -> Cursor {
+        Cursor::new()
+    }
+}
+
+impl Cursor {
+    pub fn new() -> Cursor {
+        Cursor {
+            position: Point { x: 1, y: 1 },
+            grabbed: false,
+        }
+    }
+
+    /// Moves the cursor by one slot, staying within the numbered tile slots.
+    pub fn move_towards(&mut self, direction: &Direction, size: i8) {
+        let position = self.position.add_direction(direction, 1);
+        self.position = Point {
+            x: position.x.clamp(1, size),
+            y: position.y.clamp(1, size),
+        };
+    }
+
+    /// The shift of the selected row (West/East) or column (North/South).
+    pub fn shift_towards(&self, direction: Direction) -> Shift {
+        let idx = match direction {
+            Direction::West | Direction::East => self.position.y,
+            Direction::North | Direction::South => self.position.x,
+        };
+        Shift {
+            idx: idx as u8,
+            direction,
+        }
+    }
+}
+
+pub fn arrow_direction(key: &str) -> Option<Direction> {
+    match key {
+        "ArrowUp" => Some(Direction::North),
+        "ArrowDown" => Some(Direction::South),
+        "ArrowLeft" => Some(Direction::West),
+        "ArrowRight" => Some(Direction::East),
+        _ => None,
+    }
+}
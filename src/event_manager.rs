@@ -1,3 +1,7 @@
+use crate::canvas::Picture;
+use crate::puzzle::{PuzzleState, Shift};
+use crate::replay::Playback;
+use crate::theme::Theme;
 use crate::utils::{log, window, Point};
 
 use std::cell::RefCell;
@@ -6,55 +10,192 @@ use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 
+/// Pixels scrolled per line, for wheel events that count lines
+const WHEEL_LINE_HEIGHT: f64 = 40.0;
+/// Pixels scrolled per page, for wheel events that count pages
+const WHEEL_PAGE_HEIGHT: f64 = 800.0;
+
 #[allow(dead_code)]
 pub struct EventManager {
     // These are referenced so that they are not dropped until the game is dropped
-    onpointerdown: Closure<dyn FnMut(web_sys::MouseEvent)>,
-    onpointerup: Closure<dyn FnMut(web_sys::MouseEvent)>,
-    onpointermove: Closure<dyn FnMut(web_sys::MouseEvent)>,
+    onpointerdown: Closure<dyn FnMut(web_sys::PointerEvent)>,
+    onpointerup: Closure<dyn FnMut(web_sys::PointerEvent)>,
+    onpointermove: Closure<dyn FnMut(web_sys::PointerEvent)>,
+    onpointercancel: Closure<dyn FnMut(web_sys::PointerEvent)>,
+    onkeydown: Closure<dyn FnMut(web_sys::KeyboardEvent)>,
+    onwheel: Closure<dyn FnMut(web_sys::WheelEvent)>,
     event_target: web_sys::HtmlElement,
-    event_queue: Rc<RefCell<VecDeque<MouseEvent>>>,
+    event_queue: Rc<RefCell<VecDeque<InputEvent>>>,
+}
+
+/// Every kind of input the game reacts to, whether it comes from the browser or from a script.
+pub enum InputEvent {
+    PointerDown(Pointer),
+    PointerUp(Pointer),
+    PointerMove(Pointer),
+    /// The browser has taken over the pointer, e.g. to scroll, and no up event will follow
+    PointerCancel(Pointer),
+    Key(KeyEvent),
+    /// Scrolling by the given number of pixels
+    Wheel(Point<f64>),
+    Command(Command),
+}
+
+pub struct Pointer {
+    pub id: i32,
+    /// "mouse", "pen" or "touch"
+    #[allow(dead_code)] // The game does not tell pointer types apart yet
+    pub pointer_type: String,
+    pub position: Point<i32>,
+}
+
+impl Pointer {
+    fn from_event(e: &web_sys::PointerEvent) -> Pointer {
+        Pointer {
+            id: e.pointer_id(),
+            pointer_type: e.pointer_type(),
+            position: Point {
+                x: e.client_x(),
+                y: e.client_y(),
+            },
+        }
+    }
 }
 
-pub enum MouseEvent {
-    Down(Point<i32>),
-    Up(Point<i32>),
-    Move(Point<i32>),
+pub struct KeyEvent {
+    pub key: String,
+    /// Whether Ctrl (or Cmd on Mac) was held down
+    pub ctrl: bool,
+    pub shift: bool,
+}
+
+/// Actions requested directly rather than through a pointer or key.
+pub enum Command {
+    Shift(Shift),
+    /// Make a sequence of shifts, all of which have to be legal in turn
+    Moves(Vec<Shift>),
+    Undo,
+    Redo,
+    Hint,
+    AutoSolve,
+    /// Replace the position with a decoded position code
+    Open(PuzzleState),
+    /// Shuffle a new game, reproducibly from the seed if one is given
+    NewGame(Option<u32>),
+    Replay(ReplayCommand),
+    Theme(Box<Theme>),
+    /// Cut the tiles from a picture, or go back to plain tiles
+    Picture(Option<Picture>),
+}
+
+/// Controlling the playback of a replay, which takes over the board until it is stopped.
+pub enum ReplayCommand {
+    Load(Playback),
+    Speed(f64),
+    Pause(bool),
+    /// Jump to the given time in milliseconds
+    Seek(f64),
+    /// Return to the game as it was before the replay was loaded
+    Stop,
 }
 
 impl EventManager {
     pub fn new(event_target: web_sys::HtmlElement) -> EventManager {
         let rc_event_queue = Rc::new(RefCell::new(VecDeque::new()));
 
-        let eq_mousedown = rc_event_queue.clone();
-        let cb = move |e: web_sys::MouseEvent| {
-            eq_mousedown.borrow_mut().push_back(MouseEvent::Down(Point {
-                x: e.client_x(),
-                y: e.client_y(),
-            }));
+        // Stop touches on the board from scrolling the page, which would cancel the pointer
+        event_target
+            .style()
+            .set_property("touch-action", "none")
+            .unwrap();
+
+        let eq_pointerdown = rc_event_queue.clone();
+        let capture_target = event_target.clone();
+        let cb = move |e: web_sys::PointerEvent| {
+            // Keep receiving the events of this pointer even if it leaves the board
+            let _ = capture_target.set_pointer_capture(e.pointer_id());
+            eq_pointerdown
+                .borrow_mut()
+                .push_back(InputEvent::PointerDown(Pointer::from_event(&e)));
         };
         let onpointerdown = Closure::wrap(Box::new(cb) as Box<dyn FnMut(_)>);
         event_target.set_onpointerdown(Some(onpointerdown.as_ref().unchecked_ref()));
 
-        let eq_mouseup = rc_event_queue.clone();
-        let cb = move |e: web_sys::MouseEvent| {
-            eq_mouseup.borrow_mut().push_back(MouseEvent::Up(Point {
-                x: e.client_x(),
-                y: e.client_y(),
-            }));
+        let eq_pointerup = rc_event_queue.clone();
+        let cb = move |e: web_sys::PointerEvent| {
+            eq_pointerup
+                .borrow_mut()
+                .push_back(InputEvent::PointerUp(Pointer::from_event(&e)));
         };
         let onpointerup = Closure::wrap(Box::new(cb) as Box<dyn FnMut(_)>);
-        window().set_onpointerup(Some(onpointerup.as_ref().unchecked_ref()));
+        event_target.set_onpointerup(Some(onpointerup.as_ref().unchecked_ref()));
 
-        let eq_mousemove = rc_event_queue.clone();
-        let cb = move |e: web_sys::MouseEvent| {
-            eq_mousemove.borrow_mut().push_back(MouseEvent::Move(Point {
-                x: e.client_x(),
-                y: e.client_y(),
-            }));
+        let eq_pointermove = rc_event_queue.clone();
+        let cb = move |e: web_sys::PointerEvent| {
+            eq_pointermove
+                .borrow_mut()
+                .push_back(InputEvent::PointerMove(Pointer::from_event(&e)));
         };
         let onpointermove = Closure::wrap(Box::new(cb) as Box<dyn FnMut(_)>);
-        window().set_onpointermove(Some(onpointermove.as_ref().unchecked_ref()));
+        event_target.set_onpointermove(Some(onpointermove.as_ref().unchecked_ref()));
+
+        let eq_pointercancel = rc_event_queue.clone();
+        let cb = move |e: web_sys::PointerEvent| {
+            eq_pointercancel
+                .borrow_mut()
+                .push_back(InputEvent::PointerCancel(Pointer::from_event(&e)));
+        };
+        let onpointercancel = Closure::wrap(Box::new(cb) as Box<dyn FnMut(_)>);
+        event_target.set_onpointercancel(Some(onpointercancel.as_ref().unchecked_ref()));
+
+        // Take keys only while the board has focus, which it gets when clicked or tabbed to
+        if !event_target.has_attribute("tabindex") {
+            event_target.set_tab_index(0);
+        }
+
+        let eq_keydown = rc_event_queue.clone();
+        let cb = move |e: web_sys::KeyboardEvent| {
+            // Leave typing into fields inside the element alone
+            if is_editing(&e) {
+                return;
+            }
+            // Stop the arrow keys from scrolling the page while playing
+            if is_game_key(&e) {
+                e.prevent_default();
+            }
+            eq_keydown.borrow_mut().push_back(InputEvent::Key(KeyEvent {
+                key: e.key(),
+                ctrl: e.ctrl_key() || e.meta_key(),
+                shift: e.shift_key(),
+            }));
+        };
+        let onkeydown = Closure::wrap(Box::new(cb) as Box<dyn FnMut(_)>);
+        event_target
+            .add_event_listener_with_callback("keydown", onkeydown.as_ref().unchecked_ref())
+            .unwrap();
+
+        let eq_wheel = rc_event_queue.clone();
+        let focus_target = event_target.clone();
+        let cb = move |e: web_sys::WheelEvent| {
+            // Scroll the cursor instead of the page, but only while playing with the keyboard,
+            // so that the page still scrolls past the board
+            if !has_focus(&focus_target) {
+                return;
+            }
+            e.prevent_default();
+            // Some browsers scroll by lines or pages rather than pixels
+            let scale = match e.delta_mode() {
+                web_sys::WheelEvent::DOM_DELTA_LINE => WHEEL_LINE_HEIGHT,
+                web_sys::WheelEvent::DOM_DELTA_PAGE => WHEEL_PAGE_HEIGHT,
+                _ => 1.0,
+            };
+            eq_wheel.borrow_mut().push_back(InputEvent::Wheel(Point {
+                x: e.delta_x() * scale,
+                y: e.delta_y() * scale,
+            }));
+        };
+        let onwheel = Closure::wrap(Box::new(cb) as Box<dyn FnMut(_)>);
+        event_target.set_onwheel(Some(onwheel.as_ref().unchecked_ref()));
 
         log("Added event manager");
 
@@ -62,21 +203,66 @@ impl EventManager {
             onpointerdown,
             onpointerup,
             onpointermove,
+            onpointercancel,
+            onkeydown,
+            onwheel,
             event_target,
             event_queue: rc_event_queue,
         }
     }
 
-    pub fn pop_event(&self) -> Option<MouseEvent> {
+    pub fn push_event(&self, event: InputEvent) {
+        (*self.event_queue.borrow_mut()).push_back(event)
+    }
+
+    pub fn pop_event(&self) -> Option<InputEvent> {
         (*self.event_queue.borrow_mut()).pop_front()
     }
 }
 
+/// Whether the key is one the game reacts to, and not just passed on to the queue.
+fn is_game_key(e: &web_sys::KeyboardEvent) -> bool {
+    let ctrl = e.ctrl_key() || e.meta_key();
+    match e.key().as_str() {
+        "ArrowUp" | "ArrowDown" | "ArrowLeft" | "ArrowRight" | "Enter" | "Escape" => true,
+        "z" | "Z" | "y" | "Y" => ctrl,
+        "h" | "H" | "s" | "S" => !ctrl,
+        _ => false,
+    }
+}
+
+/// Whether the key was pressed in a text field or other editable element.
+fn is_editing(e: &web_sys::KeyboardEvent) -> bool {
+    let element = match e
+        .target()
+        .and_then(|t| t.dyn_into::<web_sys::HtmlElement>().ok())
+    {
+        Some(element) => element,
+        None => return false,
+    };
+    let tag = element.tag_name();
+    element.is_content_editable() || tag == "INPUT" || tag == "TEXTAREA" || tag == "SELECT"
+}
+
+/// Whether the element has the keyboard focus, which it gets when clicked or tabbed to.
+fn has_focus(element: &web_sys::HtmlElement) -> bool {
+    window()
+        .document()
+        .and_then(|document| document.active_element())
+        .is_some_and(|active| JsValue::from(active) == JsValue::from(element.clone()))
+}
+
 impl Drop for EventManager {
     fn drop(&mut self) {
         self.event_target.set_onpointerdown(None);
-        window().set_onpointerup(None);
-        window().set_onpointermove(None);
+        self.event_target.set_onpointerup(None);
+        self.event_target.set_onpointermove(None);
+        self.event_target.set_onpointercancel(None);
+        self.event_target.set_onwheel(None);
+        let _ = self.event_target.remove_event_listener_with_callback(
+            "keydown",
+            self.onkeydown.as_ref().unchecked_ref(),
+        );
         log("Dropping event manager");
     }
 }
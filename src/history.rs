@@ -0,0 +1,36 @@
+use crate::puzzle::Shift;
+use serde::{Deserialize, Serialize};
+
+/// The shifts made by the player, allowing them to be undone and redone.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    done: Vec<Shift>,
+    undone: Vec<Shift>,
+}
+
+impl History {
+    /// Records a new shift, which discards everything that could have been redone.
+    pub fn record(&mut self, shift: Shift) {
+        self.done.push(shift);
+        self.undone.clear();
+    }
+
+    /// The shifts made so far, oldest first, without those that have been undone.
+    pub fn done(&self) -> &[Shift] {
+        &self.done
+    }
+
+    /// Returns the shift reverting the last recorded one.
+    pub fn undo(&mut self) -> Option<Shift> {
+        let shift = self.done.pop()?;
+        self.undone.push(shift);
+        Some(shift.inverse())
+    }
+
+    /// Returns the last undone shift to make again.
+    pub fn redo(&mut self) -> Option<Shift> {
+        let shift = self.undone.pop()?;
+        self.done.push(shift);
+        Some(shift)
+    }
+}
@@ -1,14 +1,47 @@
-mod board;
+pub mod animation;
+pub mod board;
+mod canvas;
+pub mod code;
+pub mod config;
+pub mod cursor;
 mod event_manager;
-mod shuffle;
+pub mod history;
+pub mod notation;
+pub mod puzzle;
+pub mod renderer;
+pub mod replay;
+pub mod rng;
+mod save;
+pub mod shuffle;
+pub mod solver;
+pub mod stats;
+mod surface;
+pub mod svg;
+pub mod text;
+pub mod theme;
 mod utils;
 
-use event_manager::{EventManager, MouseEvent};
-use shuffle::{shuffle, ShiftAction};
-use std::cell::RefCell;
+use animation::{Easing, ShiftAction};
+use canvas::{CanvasRenderer, Picture};
+use config::GameConfig;
+use cursor::{arrow_direction, Cursor};
+use event_manager::{Command, EventManager, InputEvent, KeyEvent, ReplayCommand};
+use history::History;
+use notation::{format_moves, parse_moves};
+use puzzle::{PuzzleState, Shift};
+use replay::{Playback, Replay};
+use rng::SeededRng;
+use save::SavedGame;
+use shuffle::{scramble, shuffle, Difficulty};
+use solver::{solve_for_game, MAX_AUTO_SOLVE_LENGTH};
+use stats::Stats;
+use std::cell::{Cell, RefCell};
 use std::collections::VecDeque;
 use std::rc::Rc;
-use utils::{window, Direction, Point};
+use surface::Surface;
+use svg::SvgRenderer;
+use theme::Theme;
+use utils::{log, window, Direction, Point};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 
@@ -18,56 +51,261 @@ use wasm_bindgen::JsCast;
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
-#[wasm_bindgen]
+/// How many pixels the wheel scrolls to move the cursor by one slot, about a mouse wheel notch.
+const WHEEL_STEP: f64 = 100.0;
+
 pub struct Game {
     event_manager: EventManager,
-    ctx: web_sys::CanvasRenderingContext2d,
+    surface: Surface,
     board: board::Board,
     game_state: GameState,
     actions: VecDeque<ShiftAction>,
+    animation_duration: f64,
+    /// How long a released row or column takes to move into place
+    release_duration: f64,
+    easing: Easing,
+    last_frame: Option<f64>,
+    history: History,
+    stats: Stats,
+    /// Whether the queued shifts are the solver's, which does not count as the player solving it
+    auto_solving: bool,
+    seed: u32,
+    /// How new games are shuffled
+    difficulty: Option<Difficulty>,
+    shuffle_steps: i8,
+    /// The shifts that made the position from the solved layout, if known, which lets hints
+    /// and auto-solve retrace them where searching for a solution is too slow
+    trail: Option<Vec<Shift>>,
+    /// Where the game is saved after every shift, unless persistence is disabled or the
+    /// position was opened from a code
+    save_key: Option<String>,
+    /// Milliseconds since the recording started
+    clock: f64,
+    /// The moves made since the shuffle, or since the position was opened or resumed
+    recording: Option<Replay>,
+    playback: Option<Playback>,
+    /// The game to return to once the playback is stopped
+    before_playback: Option<SavedGame>,
+    /// How far the wheel has scrolled since it last moved the cursor, in pixels
+    wheel: Point<f64>,
 }
 
 enum GameState {
     Idle,
-    Dragging(Point<i32>),
+    /// Dragging with the pointer of the given id, which went down at the given point
+    Dragging(i32, Point<i32>),
     Processing,
+    Solved,
 }
 
 impl Game {
-    fn create(event_manager: EventManager, ctx: web_sys::CanvasRenderingContext2d) {
-        let f = Rc::new(RefCell::new(None));
-        let g = f.clone();
+    /// Opens the position code of the config, or of the URL if the config allows it, if there is
+    /// one, otherwise resumes the game saved with the same size, scramble and seed if there is
+    /// one, or starts a new one. A code that cannot be decoded is ignored. Either way the saved
+    /// game is left alone, since a position opened from a code is never saved.
+    fn create(event_manager: EventManager, surface: Surface, config: &GameConfig) -> Game {
+        let url_code = || config.url_code.then(url_fragment).flatten();
+        let opened = config.code.clone().or_else(url_code).and_then(|code| {
+            let board = board::Board::from_code(&code, config.theme.clone());
+            if board.is_none() {
+                log(&format!("Ignoring invalid position code {}", code));
+            }
+            board
+        });
+        let from_code = opened.is_some();
+        let board = opened.unwrap_or_else(|| board::Board::new(config.size, config.theme.clone()));
+        let save_key = if from_code {
+            None
+        } else {
+            config.save_key(board.state().size())
+        };
 
-        let board_size = 3;
         let mut game = Game {
             event_manager,
-            ctx,
-            board: board::Board::new(board_size),
+            surface,
+            board,
             game_state: GameState::Idle,
-            actions: shuffle(board_size, 10),
+            actions: VecDeque::new(),
+            animation_duration: config.animation_duration,
+            release_duration: config.release_duration,
+            easing: config.easing,
+            last_frame: None,
+            history: History::default(),
+            stats: Stats::default(),
+            auto_solving: false,
+            seed: 0,
+            difficulty: config.difficulty,
+            shuffle_steps: config.shuffle_steps,
+            trail: None,
+            save_key,
+            clock: 0.0,
+            recording: None,
+            playback: None,
+            before_playback: None,
+            wheel: Point { x: 0.0, y: 0.0 },
+        };
+
+        if from_code {
+            log(&format!("Opened position {}", game.board.to_code()));
+            game.settle();
+            return game;
+        }
+
+        let resumed = match game.save_key.as_deref().and_then(save::load) {
+            Some(saved) if config.seed.is_none() || config.seed == Some(saved.seed) => {
+                game.resume(saved)
+            }
+            _ => false,
         };
-        let mut current_time = 0.0;
+        if !resumed {
+            game.start(config.seed);
+        }
+        game
+    }
 
-        *g.borrow_mut() = Some(Closure::wrap(Box::new(move |time: f64| {
-            let dt = time - current_time;
-            current_time = time;
+    /// Replaces the position with a decoded position code, starting the count and the timer afresh.
+    /// The game stops being saved, so that the one in progress can still be resumed later.
+    fn open(&mut self, state: PuzzleState) {
+        if self.can_open() {
+            self.save_key = None;
+            self.board.set_state(state);
+            self.board.set_cursor(None);
+            self.trail = None;
+            self.history = History::default();
+            self.stats = Stats::default();
+            self.begin_recording();
+            self.settle();
+        }
+    }
 
-            game.update(dt);
+    /// A position can only be opened while the board is at rest.
+    fn can_open(&self) -> bool {
+        matches!(self.game_state, GameState::Idle | GameState::Solved)
+    }
 
-            request_animation_frame(f.borrow().as_ref().unwrap());
-        }) as Box<dyn FnMut(_)>));
+    /// Replaces the position with a newly shuffled board, while the board is at rest.
+    fn new_game(&mut self, seed: Option<u32>) {
+        if self.can_open() {
+            let size = self.board.state().size();
+            self.board.set_state(PuzzleState::new(size));
+            self.board.set_cursor(None);
+            self.board.set_hint(None);
+            self.history = History::default();
+            self.stats = Stats::default();
+            self.recording = None;
+            self.start(seed);
+        }
+    }
+
+    fn start(&mut self, seed: Option<u32>) {
+        self.seed = seed.unwrap_or_else(rng::random_seed);
+        log(&format!("Shuffling with seed {}", self.seed));
+
+        let size = self.board.state().size();
+        let mut rng = SeededRng::new(self.seed as u64);
+        let shifts = match self.difficulty {
+            Some(difficulty) => scramble(size, difficulty, &mut rng),
+            None => shuffle(size, self.shuffle_steps, &mut rng),
+        };
+        self.trail = Some(Vec::new());
+        self.actions = shifts
+            .iter()
+            .map(|shift| ShiftAction::create(shift, self.animation_duration, self.easing))
+            .collect();
+        // Without any shifts to animate the board would never come to rest
+        if self.actions.is_empty() {
+            self.settle();
+        } else {
+            self.game_state = GameState::Processing;
+        }
+    }
+
+    /// Continues a saved game, unless it does not fit the board or has already been solved.
+    fn resume(&mut self, saved: SavedGame) -> bool {
+        let state = match saved.state() {
+            Some(state) if state.size() == self.board.state().size() && !state.is_solved() => state,
+            _ => return false,
+        };
+
+        log(&format!("Resuming saved game with seed {}", saved.seed));
+        self.restore(state, saved);
+        self.begin_recording();
+        true
+    }
+
+    fn restore(&mut self, state: PuzzleState, saved: SavedGame) {
+        self.board.set_state(state);
+        self.seed = saved.seed;
+        self.stats = saved.stats;
+        self.history = saved.history;
+        self.trail = saved.trail;
+    }
+
+    fn snapshot(&self) -> SavedGame {
+        SavedGame {
+            seed: self.seed,
+            size: self.board.state().size(),
+            cells: self.board.state().cells().to_vec(),
+            stats: self.stats.clone(),
+            history: self.history.clone(),
+            trail: self.trail.clone(),
+        }
+    }
 
-        request_animation_frame(g.borrow().as_ref().unwrap());
+    fn save(&self) {
+        if let Some(key) = &self.save_key {
+            save::store(key, &self.snapshot());
+        }
+    }
+
+    /// Starts recording the moves from the current position.
+    fn begin_recording(&mut self) {
+        self.clock = 0.0;
+        self.recording = Some(Replay::new(self.board.state()));
+    }
+
+    fn record(&mut self, shift: &Shift, counted: bool) {
+        if let Some(recording) = &mut self.recording {
+            recording.record(self.clock, *shift, counted);
+        }
+    }
+
+    /// Animates a shift, recording it as a move of the player if it is `counted`, and as an
+    /// undo, a redo or a step of the solver otherwise.
+    fn animate(&mut self, shift: &Shift, counted: bool) {
+        self.actions.push_back(ShiftAction::create(
+            shift,
+            self.animation_duration,
+            self.easing,
+        ));
+        self.record(shift, counted);
+        // Later input in the same frame must wait for the shift to be animated
+        self.game_state = GameState::Processing;
+    }
+
+    fn frame(&mut self, time: f64) {
+        let dt = self.last_frame.map_or(0.0, |last| time - last);
+        self.last_frame = Some(time);
+
+        self.update(dt);
+    }
+
+    /// Makes the next frame start afresh, so that the time spent paused is not animated.
+    fn pause(&mut self) {
+        self.last_frame = None;
     }
 
     fn update(&mut self, dt: f64) {
+        // Watching a replay is not part of the recorded game
+        if self.playback.is_none() {
+            self.clock += dt;
+        }
+        self.stats.tick(dt);
+        self.advance_playback(dt);
         self.process_actions(dt);
 
-        loop {
-            match self.event_manager.pop_event() {
-                Some(ev) => self.process_event(ev),
-                None => break,
-            };
+        while let Some(ev) = self.event_manager.pop_event() {
+            self.process_event(ev);
         }
 
         self.render()
@@ -77,128 +315,735 @@ impl Game {
         if let Some(action) = self.actions.front_mut() {
             self.game_state = GameState::Processing;
 
-            let tile_size = self.board.tile_size();
-            action.current_offset +=
-                ((dt / action.remaining_time) * (tile_size - action.current_offset)).min(tile_size);
-            let from = match action.direction {
-                Direction::North | Direction::South => Point {
-                    x: ((action.idx as f64) + 0.5) * tile_size,
-                    y: 1.5 * tile_size,
-                },
-                Direction::West | Direction::East => Point {
-                    x: 1.5 * tile_size,
-                    y: ((action.idx as f64) + 0.5) * tile_size,
-                },
-            };
-            let to = from.add_direction(&action.direction, action.current_offset);
-            action.remaining_time -= dt;
-
-            if action.remaining_time < 0.0 {
-                self.board.shift(&from, &to, true);
+            let progress = action.progress.advance(dt);
+            let shift = action.shift();
+            if action.progress.is_finished() {
+                let commit = action.commit;
                 self.actions.pop_front();
+                if !commit {
+                    self.board.cancel_shift();
+                } else if !self.board.apply_shift(&shift) {
+                    // The shifts queued after it were meant for the board it would have left
+                    log(&format!(
+                        "Dropping illegal shift {} and those after it",
+                        shift
+                    ));
+                    self.actions.clear();
+                } else if let Some(trail) = &mut self.trail {
+                    trail.push(shift);
+                }
                 if self.actions.is_empty() {
-                    self.game_state = GameState::Idle;
+                    self.settle();
                 }
             } else {
-                self.board.shift(&from, &to, false);
+                self.board
+                    .displace(&shift, progress * self.board.tile_size());
             }
         }
     }
 
-    fn render(&self) {
-        let canvas = self.ctx.canvas().unwrap();
-        let scale_x = canvas.width() as f64 / board::BOARD_SIZE;
-        let scale_y = canvas.height() as f64 / board::BOARD_SIZE;
-        self.ctx.reset_transform().unwrap();
-        self.ctx.scale(scale_x, scale_y).unwrap();
-        self.ctx.set_font("128px Arial");
-        self.ctx.set_text_align("center");
-        self.ctx.set_text_baseline("middle");
+    fn render(&mut self) {
+        let board = &self.board;
+        let stats = &self.stats;
+        let solved = matches!(self.game_state, GameState::Solved);
+        self.surface.draw(|renderer| {
+            board.render(renderer, stats);
+            if solved {
+                board.render_solved_overlay(renderer);
+            }
+        });
+    }
+
+    fn process_event(&mut self, event: InputEvent) {
+        // The replay has the board to itself until it is stopped
+        if self.playback.is_some() {
+            match event {
+                InputEvent::Command(Command::Replay(command)) => self.process_replay(command),
+                InputEvent::Command(Command::Theme(theme)) => self.board.set_theme(*theme),
+                InputEvent::Command(Command::Picture(picture)) => self.set_picture(picture),
+                _ => (),
+            }
+            return;
+        }
 
-        self.board.render(&self.ctx);
+        match event {
+            InputEvent::Key(key) => self.process_key(&key),
+            InputEvent::Wheel(delta) => self.process_wheel(&delta),
+            InputEvent::Command(command) => self.process_command(command),
+            _ => self.process_pointer_event(event),
+        }
     }
 
-    fn process_event(&mut self, event: MouseEvent) {
+    fn process_pointer_event(&mut self, event: InputEvent) {
         match self.game_state {
             GameState::Idle => {
-                if let MouseEvent::Down(point) = event {
-                    self.game_state = GameState::Dragging(point);
+                if let InputEvent::PointerDown(pointer) = event {
+                    self.game_state = GameState::Dragging(pointer.id, pointer.position);
                 }
             }
-            GameState::Dragging(from) => match event {
-                MouseEvent::Move(to) => self.process_dragging(from, to),
-                MouseEvent::Up(to) => self.process_drag_over(from, to),
-                MouseEvent::Down(to) => self.process_drag_over(from, to),
+            // Any other pointer is ignored while dragging
+            GameState::Dragging(id, from) => match event {
+                InputEvent::PointerMove(pointer) if pointer.id == id => {
+                    self.process_dragging(from, pointer.position)
+                }
+                InputEvent::PointerUp(pointer) | InputEvent::PointerDown(pointer)
+                    if pointer.id == id =>
+                {
+                    self.process_drag_over(from, pointer.position)
+                }
+                InputEvent::PointerCancel(pointer) if pointer.id == id => {
+                    self.board.cancel_shift();
+                    self.game_state = GameState::Idle;
+                }
+                _ => (),
             },
             _ => (),
         }
     }
 
+    fn process_command(&mut self, command: Command) {
+        match command {
+            Command::Shift(shift) => self.player_shifts(&[shift]),
+            Command::Moves(shifts) => self.player_shifts(&shifts),
+            Command::Undo => self.undo(),
+            Command::Redo => self.redo(),
+            Command::Hint => self.hint(),
+            Command::AutoSolve => self.auto_solve(),
+            Command::Open(state) => self.open(state),
+            Command::NewGame(seed) => self.new_game(seed),
+            Command::Replay(command) => self.process_replay(command),
+            Command::Theme(theme) => self.board.set_theme(*theme),
+            Command::Picture(picture) => self.set_picture(picture),
+        }
+    }
+
+    fn set_picture(&mut self, picture: Option<Picture>) {
+        let shown = self.surface.set_picture(picture);
+        self.board.set_picture(shown);
+    }
+
+    fn process_replay(&mut self, command: ReplayCommand) {
+        match (command, &mut self.playback) {
+            (ReplayCommand::Load(playback), _) => self.load_playback(playback),
+            (ReplayCommand::Speed(speed), Some(playback)) => playback.set_speed(speed),
+            (ReplayCommand::Pause(paused), Some(playback)) => playback.set_paused(paused),
+            (ReplayCommand::Seek(time), Some(playback)) => {
+                let state = playback.seek(time);
+                self.stats = Stats::at(playback.counted() as u32, playback.time());
+                self.actions.clear();
+                self.board.set_state(state);
+                self.settle();
+            }
+            (ReplayCommand::Stop, Some(_)) => {
+                self.playback = None;
+                self.actions.clear();
+                if let Some(saved) = self.before_playback.take() {
+                    if let Some(state) = saved.state() {
+                        self.restore(state, saved);
+                    }
+                }
+                self.settle();
+            }
+            _ => (),
+        }
+    }
+
+    /// A replay can replace another one at any time, but the game only while it is at rest.
+    fn can_load_playback(&self) -> bool {
+        match self.game_state {
+            GameState::Idle | GameState::Solved => true,
+            GameState::Dragging(..) | GameState::Processing => self.playback.is_some(),
+        }
+    }
+
+    /// Shows the starting position of the replay, remembering the game to return to.
+    fn load_playback(&mut self, playback: Playback) {
+        if !self.can_load_playback() {
+            return;
+        }
+        if self.playback.is_none() {
+            self.before_playback = Some(self.snapshot());
+        }
+
+        self.actions.clear();
+        self.board.set_state(playback.start().clone());
+        self.board.set_cursor(None);
+        self.trail = None;
+        self.stats = Stats::default();
+        self.playback = Some(playback);
+        self.settle();
+    }
+
+    /// Animates the moves of the replay that have become due.
+    fn advance_playback(&mut self, dt: f64) {
+        if let Some(playback) = &mut self.playback {
+            let due = playback.advance(dt);
+            // Keep up with the replay when it is played faster than the shifts are animated
+            let duration = self.animation_duration / playback.speed().max(1.0);
+            let easing = self.easing;
+            self.actions.extend(
+                due.iter()
+                    .map(|shift| ShiftAction::create(shift, duration, easing)),
+            );
+            self.stats = Stats::at(playback.counted() as u32, playback.time());
+        }
+    }
+
+    fn process_key(&mut self, event: &KeyEvent) {
+        if let GameState::Solved = self.game_state {
+            return;
+        }
+        if let Some(direction) = arrow_direction(&event.key) {
+            self.process_arrow(direction, event.shift);
+            return;
+        }
+
+        match (event.ctrl, event.key.as_str()) {
+            (true, "z") | (true, "Z") if event.shift => self.process_command(Command::Redo),
+            (true, "z") | (true, "Z") => self.process_command(Command::Undo),
+            (true, "y") | (true, "Y") => self.process_command(Command::Redo),
+            (false, "h") | (false, "H") => self.process_command(Command::Hint),
+            (false, "s") | (false, "S") => self.process_command(Command::AutoSolve),
+            (false, "Enter") => {
+                if let Some(mut cursor) = self.board.cursor() {
+                    cursor.grabbed = !cursor.grabbed;
+                    self.board.set_cursor(Some(cursor));
+                }
+            }
+            (false, "Escape") => self.board.set_cursor(None),
+            _ => (),
+        }
+    }
+
+    /// Moves the cursor, or shifts its row or column if Shift is held or the cursor is grabbed.
+    /// The first arrow key press only reveals the cursor.
+    fn process_arrow(&mut self, direction: Direction, shift_held: bool) {
+        let mut cursor = match self.board.cursor() {
+            Some(cursor) => cursor,
+            None => {
+                self.board.set_cursor(Some(Cursor::new()));
+                return;
+            }
+        };
+
+        if shift_held || cursor.grabbed {
+            self.process_command(Command::Shift(cursor.shift_towards(direction)));
+        } else {
+            cursor.move_towards(&direction, self.board.state().size());
+            self.board.set_cursor(Some(cursor));
+        }
+    }
+
+    /// Scrolling over the focused board moves the cursor like the arrow keys do, one slot for
+    /// every `WHEEL_STEP` pixels, so that a trackpad swipe does not fly across the board.
+    fn process_wheel(&mut self, delta: &Point<f64>) {
+        if let GameState::Solved = self.game_state {
+            return;
+        }
+        let wheel = self.wheel + *delta;
+        let (distance, forward, backward) = if wheel.x.abs() > wheel.y.abs() {
+            (wheel.x, Direction::East, Direction::West)
+        } else {
+            (wheel.y, Direction::South, Direction::North)
+        };
+        if distance.abs() < WHEEL_STEP {
+            self.wheel = wheel;
+            return;
+        }
+
+        self.wheel = Point { x: 0.0, y: 0.0 };
+        let direction = if distance > 0.0 { forward } else { backward };
+        self.process_arrow(direction, false);
+    }
+
+    /// Animates shifts made by the player other than by dragging, if they are legal in turn.
+    fn player_shifts(&mut self, shifts: &[Shift]) {
+        if let GameState::Idle = self.game_state {
+            let mut state = self.board.state().clone();
+            if let Some(illegal) = shifts.iter().find(|shift| !state.apply(shift)) {
+                log(&format!("Ignoring moves from illegal move {}", illegal));
+                return;
+            }
+
+            for shift in shifts {
+                self.animate(shift, true);
+                self.history.record(*shift);
+                self.stats.record_move();
+            }
+        }
+    }
+
     fn process_dragging(&mut self, from: Point<i32>, to: Point<i32>) {
         let scaled_to = (to - from) / 2 + from;
-        self.board.shift(
+        self.board.drag(
             &self.to_board_point(&from),
             &self.to_board_point(&scaled_to),
-            false,
         );
     }
 
+    /// Animates the released row or column into place: shifted if it was dragged more than
+    /// half way, and back where it was otherwise.
     fn process_drag_over(&mut self, from: Point<i32>, to: Point<i32>) {
-        let scaled_to = (to - from) / 2 + from;
-        self.board.shift(
-            &self.to_board_point(&from),
-            &self.to_board_point(&scaled_to),
-            true,
-        );
-        self.game_state = GameState::Idle;
+        self.process_dragging(from, to);
+        let (shift, progress) = match self.board.displacement() {
+            Some(displacement) => displacement,
+            None => {
+                self.board.cancel_shift();
+                self.settle();
+                return;
+            }
+        };
+
+        let commit = progress > 0.5 && self.board.state().is_legal(&shift);
+        if commit {
+            self.record(&shift, true);
+            self.history.record(shift);
+            self.stats.record_move();
+        }
+        self.actions.push_back(ShiftAction::release(
+            &shift,
+            progress,
+            commit,
+            self.release_duration,
+            self.easing,
+        ));
+        self.game_state = GameState::Processing;
+    }
+
+    /// Returns to idle once the board has come to rest after a shift, unless it is solved.
+    fn settle(&mut self) {
+        if self.board.is_solved() {
+            self.game_state = GameState::Solved;
+            // Only the player's own moves count as solving it, not the shuffle, the solver
+            // or a replay
+            if self.playback.is_none() && !self.auto_solving && self.stats.moves() > 0 {
+                self.stats.stop();
+            } else {
+                self.stats.pause();
+            }
+        } else {
+            self.game_state = GameState::Idle;
+        }
+        self.auto_solving = false;
+        // Neither the shuffle nor a replay is part of the game
+        if self.playback.is_none() {
+            if self.recording.is_none() {
+                self.begin_recording();
+            }
+            self.save();
+        }
     }
 
     fn to_board_point(&self, point: &Point<i32>) -> Point<f64> {
-        let element: web_sys::HtmlElement = self.ctx.canvas().unwrap().unchecked_into();
+        let element = self.surface.element();
         let canvas_rect = element.get_bounding_client_rect();
 
-        // Calculate the origin coordinates in the boards coordinate system
-        let x = ((point.x as f64 - canvas_rect.x()) / canvas_rect.width()) * board::BOARD_SIZE;
-        let y = ((point.y as f64 - canvas_rect.y()) / canvas_rect.height()) * board::BOARD_SIZE;
+        // Undo the surface's transform from the element's corner
+        self.surface.transform().to_board(Point {
+            x: point.x as f64 - canvas_rect.x(),
+            y: point.y as f64 - canvas_rect.y(),
+        })
+    }
+
+    /// Highlights the first shift of a solution from the current position, as `solve_for_game`
+    /// finds it.
+    fn hint(&mut self) {
+        if let GameState::Idle = self.game_state {
+            let hint = solve_for_game(self.board.state(), self.trail.as_deref())
+                .and_then(|solution| solution.shifts.first().copied());
+            if hint.is_none() {
+                log("No hint available");
+            }
+            self.board.set_hint(hint);
+        }
+    }
+
+    /// Animates the board back to its solved layout along a solution `solve_for_game` finds.
+    /// Solutions longer than `MAX_AUTO_SOLVE_LENGTH` are logged and not played.
+    fn auto_solve(&mut self) {
+        if let GameState::Idle = self.game_state {
+            match solve_for_game(self.board.state(), self.trail.as_deref()) {
+                Some(solution) if solution.shifts.len() > MAX_AUTO_SOLVE_LENGTH => log(&format!(
+                    "Not solving with a solution of {} shifts, more than {}",
+                    solution.shifts.len(),
+                    MAX_AUTO_SOLVE_LENGTH
+                )),
+                Some(solution) => {
+                    log(&format!("Solving with {}", format_moves(&solution.shifts)));
+                    self.board.set_hint(None);
+                    self.auto_solving = true;
+                    for shift in &solution.shifts {
+                        self.animate(shift, false);
+                    }
+                }
+                None => log("No solution found within the search budget"),
+            }
+        }
+    }
+
+    /// Animates reverting the last shift made by the player.
+    fn undo(&mut self) {
+        if let GameState::Idle = self.game_state {
+            if let Some(shift) = self.history.undo() {
+                self.board.set_hint(None);
+                self.animate(&shift, false);
+            }
+        }
+    }
+
+    /// Animates making the last undone shift again.
+    fn redo(&mut self) {
+        if let GameState::Idle = self.game_state {
+            if let Some(shift) = self.history.redo() {
+                self.board.set_hint(None);
+                self.animate(&shift, false);
+            }
+        }
+    }
+}
+
+type FrameCallback = Closure<dyn FnMut(f64)>;
+
+/// Gives JavaScript control over a running game.
+///
+/// The game keeps running until the handle is destroyed or freed, which cancels the
+/// animation frame and drops the game along with its event listeners.
+#[wasm_bindgen]
+pub struct GameHandle {
+    game: Rc<RefCell<Game>>,
+    frame_callback: Rc<RefCell<Option<FrameCallback>>>,
+    frame_request: Rc<Cell<Option<i32>>>,
+}
+
+impl GameHandle {
+    fn start(game: Game) -> GameHandle {
+        let game = Rc::new(RefCell::new(game));
+        let frame_callback: Rc<RefCell<Option<FrameCallback>>> = Rc::new(RefCell::new(None));
+        let frame_request = Rc::new(Cell::new(None));
+
+        let g = game.clone();
+        let f = frame_callback.clone();
+        let r = frame_request.clone();
+        *frame_callback.borrow_mut() = Some(Closure::wrap(Box::new(move |time: f64| {
+            g.borrow_mut().frame(time);
+
+            r.set(Some(request_animation_frame(f.borrow().as_ref().unwrap())));
+        }) as Box<dyn FnMut(_)>));
+
+        let handle = GameHandle {
+            game,
+            frame_callback,
+            frame_request,
+        };
+        handle.resume();
+        handle
+    }
+
+    /// Queues the command to be processed with the rest of the input on the next frame.
+    fn command(&self, command: Command) {
+        self.game
+            .borrow()
+            .event_manager
+            .push_event(InputEvent::Command(command));
+    }
+}
+
+#[wasm_bindgen]
+impl GameHandle {
+    /// Highlights the next shift towards solving the puzzle once the input queued before it
+    /// has been processed. Nothing is shown while the board is moving or solved.
+    pub fn hint(&self) {
+        self.command(Command::Hint);
+    }
+
+    /// Whether a hint is highlighted, which a requested one is from the next frame on if the
+    /// solver found one.
+    pub fn has_hint(&self) -> bool {
+        self.game.borrow().board.hint().is_some()
+    }
+
+    pub fn auto_solve(&self) {
+        self.command(Command::AutoSolve);
+    }
+
+    pub fn undo(&self) {
+        self.command(Command::Undo);
+    }
+
+    pub fn redo(&self) {
+        self.command(Command::Redo);
+    }
+
+    /// Shuffles a new game of the same size and kind, reproducibly from `seed` if one is
+    /// given. Nothing happens while the board is moving.
+    pub fn new_game(&self, seed: Option<u32>) {
+        self.command(Command::NewGame(seed));
+    }
+
+    /// A position code of the current arrangement, for sharing it.
+    pub fn code(&self) -> String {
+        self.game.borrow().board.to_code()
+    }
+
+    /// Opens the position of a code made by `code`, returning whether the code was valid and
+    /// could be opened, which it cannot while the board is moving. The opened position is not
+    /// saved over the game in progress.
+    pub fn open_code(&self, code: &str) -> bool {
+        match code::decode(code) {
+            Some(state) => {
+                if !self.game.borrow().can_open() {
+                    log("Ignoring position code while the board is moving");
+                    return false;
+                }
+                self.command(Command::Open(state));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Makes the moves written in move notation, e.g. `R2> C1^`, returning whether they
+    /// could be parsed. Nothing is moved unless every move is legal in turn.
+    pub fn submit_moves(&self, moves: &str) -> bool {
+        match parse_moves(moves) {
+            Ok(shifts) => {
+                self.command(Command::Moves(shifts));
+                true
+            }
+            Err(e) => {
+                log(&format!("Ignoring moves: {}", e));
+                false
+            }
+        }
+    }
+
+    /// The shifts made by the player in move notation, without those that have been undone.
+    pub fn history(&self) -> String {
+        format_moves(self.game.borrow().history.done())
+    }
+
+    /// The moves made since the shuffle with their timestamps as JSON, see `replay::Replay`.
+    pub fn export_replay(&self) -> Option<String> {
+        self.game.borrow().recording.as_ref().map(Replay::to_json)
+    }
+
+    /// Plays back a replay exported by `export_replay`, returning whether it is valid and
+    /// could be loaded, which it cannot while the board is moving. The replay takes over the
+    /// board until it is stopped.
+    pub fn load_replay(&self, json: &str) -> bool {
+        let replay = match Replay::from_json(json) {
+            Ok(replay) => replay,
+            Err(e) => {
+                log(&format!("Ignoring unreadable replay: {}", e));
+                return false;
+            }
+        };
+        match Playback::new(replay) {
+            Some(playback) => {
+                if !self.game.borrow().can_load_playback() {
+                    log("Ignoring replay while the board is moving");
+                    return false;
+                }
+                self.command(Command::Replay(ReplayCommand::Load(playback)));
+                true
+            }
+            None => {
+                log("Ignoring replay with illegal moves");
+                false
+            }
+        }
+    }
+
+    /// How many times faster than the original the replay is played.
+    pub fn set_replay_speed(&self, speed: f64) {
+        self.command(Command::Replay(ReplayCommand::Speed(speed)));
+    }
+
+    pub fn pause_replay(&self) {
+        self.command(Command::Replay(ReplayCommand::Pause(true)));
+    }
+
+    pub fn resume_replay(&self) {
+        self.command(Command::Replay(ReplayCommand::Pause(false)));
+    }
+
+    /// Jumps to the given time in the replay, in milliseconds.
+    pub fn seek_replay(&self, time: f64) {
+        self.command(Command::Replay(ReplayCommand::Seek(time)));
+    }
+
+    /// Returns to the game as it was before the replay was loaded.
+    pub fn stop_replay(&self) {
+        self.command(Command::Replay(ReplayCommand::Stop));
+    }
+
+    /// The current time in the replay in milliseconds, or `None` without a replay.
+    pub fn replay_time(&self) -> Option<f64> {
+        self.game.borrow().playback.as_ref().map(Playback::time)
+    }
+
+    pub fn replay_duration(&self) -> Option<f64> {
+        self.game.borrow().playback.as_ref().map(Playback::duration)
+    }
+
+    /// Switches to a built-in theme: `light`, `dark` or `high-contrast`. Returns whether the
+    /// theme exists.
+    pub fn set_theme(&self, name: &str) -> bool {
+        match Theme::by_name(name) {
+            Some(theme) => {
+                self.command(Command::Theme(Box::new(theme)));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Switches to a theme given as JSON, see `theme::Theme`. Returns whether it could be read.
+    pub fn set_custom_theme(&self, json: &str) -> bool {
+        match Theme::from_json(json) {
+            Ok(theme) => {
+                self.command(Command::Theme(Box::new(theme)));
+                true
+            }
+            Err(e) => {
+                log(&format!("Ignoring unreadable theme: {}", e));
+                false
+            }
+        }
+    }
+
+    /// Turns the game into a picture puzzle, with every tile showing its part of the largest
+    /// centred square of the image. The labels are faint unless the theme hides them.
+    /// Without an image the tiles are plain again.
+    pub fn set_picture(&self, image: Option<web_sys::HtmlImageElement>) {
+        self.command(Command::Picture(image.map(Picture::Image)));
+    }
 
-        Point { x, y }
+    /// Like `set_picture`, but with an `ImageBitmap`, which can only be shown on a canvas.
+    pub fn set_picture_bitmap(&self, bitmap: Option<web_sys::ImageBitmap>) {
+        self.command(Command::Picture(bitmap.map(Picture::Bitmap)));
+    }
+
+    /// The board as it is currently shown, as an SVG document.
+    pub fn to_svg(&self) -> String {
+        let game = self.game.borrow();
+        let mut renderer = SvgRenderer::new();
+        game.board.render(&mut renderer, &game.stats);
+        if let GameState::Solved = game.game_state {
+            game.board.render_solved_overlay(&mut renderer);
+        }
+        renderer.to_svg()
+    }
+
+    /// The number of shifts the player has made.
+    pub fn moves(&self) -> u32 {
+        self.game.borrow().stats.moves()
+    }
+
+    /// The time spent playing since the first shift, in milliseconds.
+    pub fn elapsed(&self) -> f64 {
+        self.game.borrow().stats.elapsed()
+    }
+
+    /// Stops updating and rendering the game until it is resumed.
+    pub fn pause(&self) {
+        if let Some(request) = self.frame_request.take() {
+            window()
+                .cancel_animation_frame(request)
+                .expect("should cancel `requestAnimationFrame` OK");
+        }
+        self.game.borrow_mut().pause();
+    }
+
+    pub fn resume(&self) {
+        if self.frame_request.get().is_some() {
+            return;
+        }
+        if let Some(callback) = self.frame_callback.borrow().as_ref() {
+            self.frame_request
+                .set(Some(request_animation_frame(callback)));
+        }
+    }
+
+    /// Stops the game for good. The handle cannot be used afterwards.
+    pub fn destroy(self) {
+        // Dropping the handle releases the game
     }
 }
 
+impl Drop for GameHandle {
+    fn drop(&mut self) {
+        self.pause();
+        // The frame callback holds on to the game, so the game is only dropped once it is released
+        self.frame_callback.borrow_mut().take();
+        log("Destroyed game");
+    }
+}
+
+/// Starts a new game, shuffled reproducibly from `seed` if one is given.
+///
+/// With a `difficulty` the shuffle aims for a shortest solution in its range, but settles
+/// for the furthest position it could check on boards too small or too large for that, see
+/// `shuffle::scramble`.
+#[wasm_bindgen]
+pub fn setup_game(seed: Option<u32>, difficulty: Option<Difficulty>) -> GameHandle {
+    setup_game_with_config(&GameConfig {
+        seed,
+        difficulty,
+        ..GameConfig::default()
+    })
+}
+
+/// Starts a new game as configured, returning a handle to control it from JavaScript.
 #[wasm_bindgen]
-pub fn setup_game() {
+pub fn setup_game_with_config(config: &GameConfig) -> GameHandle {
     utils::set_panic_hook();
-    let (canvas, ctx) = get_context("puzzle-canvas");
-    let event_manager = EventManager::new(canvas.unchecked_into::<web_sys::HtmlElement>());
+    let surface = match &config.svg_container {
+        Some(container) => Surface::svg(container.clone()),
+        None => {
+            let canvas = match &config.canvas {
+                Some(canvas) => canvas.clone(),
+                None => get_canvas(&config.canvas_id),
+            };
+            Surface::Canvas(CanvasRenderer::new(get_context(&canvas)))
+        }
+    };
+    let event_manager = EventManager::new(surface.element());
 
-    Game::create(event_manager, ctx);
+    GameHandle::start(Game::create(event_manager, surface, config))
 }
 
-fn request_animation_frame(f: &Closure<dyn FnMut(f64)>) {
+fn request_animation_frame(f: &FrameCallback) -> i32 {
     window()
         .request_animation_frame(f.as_ref().unchecked_ref())
-        .expect("should register `requestAnimationFrame` OK");
+        .expect("should register `requestAnimationFrame` OK")
 }
 
-fn get_context(
-    canvas_id: &str,
-) -> (
-    web_sys::HtmlCanvasElement,
-    web_sys::CanvasRenderingContext2d,
-) {
+/// The fragment of the page URL without the leading `#`, if there is one.
+fn url_fragment() -> Option<String> {
+    let hash = window().location().hash().ok()?;
+    let code = hash.trim_start_matches('#');
+    if code.is_empty() {
+        None
+    } else {
+        Some(code.to_string())
+    }
+}
+
+fn get_canvas(canvas_id: &str) -> web_sys::HtmlCanvasElement {
     let document = web_sys::window().unwrap().document().unwrap();
     let canvas = document.get_element_by_id(canvas_id).unwrap();
-    let canvas = canvas
+    canvas
         .dyn_into::<web_sys::HtmlCanvasElement>()
         .map_err(|_| ())
-        .unwrap();
+        .unwrap()
+}
 
-    let ctx: web_sys::CanvasRenderingContext2d = canvas
+fn get_context(canvas: &web_sys::HtmlCanvasElement) -> web_sys::CanvasRenderingContext2d {
+    canvas
         .get_context("2d")
         .unwrap()
         .unwrap()
         .dyn_into::<web_sys::CanvasRenderingContext2d>()
-        .unwrap();
-
-    return (canvas, ctx);
+        .unwrap()
 }
@@ -0,0 +1,85 @@
+//! A textual notation for shifts, used for logging, replays and solver output.
+//!
+//! A shift is written as `R` or `C` for a row or column, its index counted from 1,
+//! and an arrow for its direction: `>` east or `<` west for rows, `v` south or `^`
+//! north for columns. `R2>` shifts the second row east and `C1^` shifts the first
+//! column north. Sequences of shifts are separated by whitespace.
+use crate::puzzle::{Direction, Shift};
+use std::fmt;
+use std::str::FromStr;
+
+/// Why a move could not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseMoveError {
+    /// The move does not start with `R` or `C`
+    Line(String),
+    /// The index is missing, zero or too large
+    Index(String),
+    /// The arrow is missing or does not fit a row or column
+    Direction(String),
+}
+
+impl fmt::Display for ParseMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseMoveError::Line(text) => write!(f, "{} does not start with R or C", text),
+            ParseMoveError::Index(text) => write!(f, "{} has no valid index", text),
+            ParseMoveError::Direction(text) => write!(f, "{} has no valid direction", text),
+        }
+    }
+}
+
+impl std::error::Error for ParseMoveError {}
+
+impl fmt::Display for Shift {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (line, arrow) = match self.direction {
+            Direction::East => ('R', '>'),
+            Direction::West => ('R', '<'),
+            Direction::South => ('C', 'v'),
+            Direction::North => ('C', '^'),
+        };
+        write!(f, "{}{}{}", line, self.idx, arrow)
+    }
+}
+
+impl FromStr for Shift {
+    type Err = ParseMoveError;
+
+    fn from_str(text: &str) -> Result<Shift, ParseMoveError> {
+        let mut chars = text.chars();
+        let is_row = match chars.next() {
+            Some('R') | Some('r') => true,
+            Some('C') | Some('c') => false,
+            _ => return Err(ParseMoveError::Line(text.to_string())),
+        };
+        let arrow = chars.next_back();
+        let direction = match (is_row, arrow) {
+            (true, Some('>')) => Direction::East,
+            (true, Some('<')) => Direction::West,
+            (false, Some('v')) | (false, Some('V')) => Direction::South,
+            (false, Some('^')) => Direction::North,
+            _ => return Err(ParseMoveError::Direction(text.to_string())),
+        };
+        let digits = chars.as_str();
+        let idx = match digits.parse::<u8>() {
+            Ok(idx) if idx > 0 && digits.bytes().all(|b| b.is_ascii_digit()) => idx,
+            _ => return Err(ParseMoveError::Index(text.to_string())),
+        };
+        Ok(Shift { idx, direction })
+    }
+}
+
+/// Parses whitespace separated moves, failing on the first invalid one.
+pub fn parse_moves(text: &str) -> Result<Vec<Shift>, ParseMoveError> {
+    text.split_whitespace().map(str::parse).collect()
+}
+
+/// Formats the moves separated by single spaces.
+pub fn format_moves(shifts: &[Shift]) -> String {
+    shifts
+        .iter()
+        .map(Shift::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,283 @@
+pub use crate::utils::{Direction, Point};
+use serde::{Deserialize, Serialize};
+
+/// The content of a single slot on the grid, including the border slots
+/// surrounding the numbered tiles.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Cell {
+    /// No tile occupies the slot
+    Vacant,
+    /// An empty tile without a label
+    Empty,
+    /// A numbered tile
+    Number(u8),
+}
+
+impl Cell {
+    pub fn label(&self) -> String {
+        match self {
+            Cell::Number(n) => format!("{}", n),
+            _ => "".to_string(),
+        }
+    }
+}
+
+/// Shifting a single row (West/East) or column (North/South) by one slot.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Shift {
+    pub idx: u8,
+    pub direction: Direction,
+}
+
+impl Shift {
+    pub fn is_row(&self) -> bool {
+        match self.direction {
+            Direction::West | Direction::East => true,
+            Direction::North | Direction::South => false,
+        }
+    }
+
+    pub fn inverse(&self) -> Shift {
+        let direction = match self.direction {
+            Direction::North => Direction::South,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+            Direction::East => Direction::West,
+        };
+        Shift {
+            idx: self.idx,
+            direction,
+        }
+    }
+}
+
+/// The arrangement of the tiles on the grid, without any knowledge of how it is rendered.
+///
+/// The numbered tiles occupy the inner `size * size` slots, while the slots
+/// surrounding them hold one empty tile for each row and each column. Every
+/// row and column always has exactly one legal shift: towards the side of the
+/// border that is currently vacant.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PuzzleState {
+    size: i8,
+    cells: Vec<Cell>,
+}
+
+impl PuzzleState {
+    /// The solved layout of a board with `size` rows and columns of numbered tiles.
+    ///
+    /// Panics unless the tiles can be numbered with a `u8`, i.e. for sizes of 1 to 15.
+    pub fn new(size: i8) -> PuzzleState {
+        assert!(
+            Self::is_valid_size(size),
+            "a board of size {} cannot be numbered",
+            size
+        );
+        let width = (size + 2) as usize;
+        let mut state = PuzzleState {
+            size,
+            cells: vec![Cell::Vacant; width * width],
+        };
+
+        for x in 1..size + 1 {
+            for y in 1..size + 1 {
+                state.set(x, y, state.home(x, y));
+            }
+            state.set(x, 0, Cell::Empty);
+            state.set(0, x, Cell::Empty);
+        }
+
+        state
+    }
+
+    /// Builds a state from all of its slots in row major order, including the border slots.
+    ///
+    /// Returns `None` unless every numbered and empty tile is present exactly once, the
+    /// numbered slots are all occupied, and every row and column has exactly one tile
+    /// at either end of the border.
+    pub fn from_cells(size: i8, cells: Vec<Cell>) -> Option<PuzzleState> {
+        if !Self::is_valid_size(size) {
+            return None;
+        }
+        let width = (size + 2) as usize;
+        if cells.len() != width * width {
+            return None;
+        }
+
+        let state = PuzzleState { size, cells };
+        let last = size + 1;
+        let occupied = |x, y| state.get(x, y) != Cell::Vacant;
+        let corners = [(0, 0), (0, last), (last, 0), (last, last)];
+        if corners.iter().any(|(x, y)| occupied(*x, *y)) {
+            return None;
+        }
+        for i in 1..last {
+            if occupied(0, i) == occupied(last, i) || occupied(i, 0) == occupied(i, last) {
+                return None;
+            }
+            if (1..last).any(|j| !occupied(i, j)) {
+                return None;
+            }
+        }
+
+        let mut numbers = vec![false; (size as usize) * (size as usize)];
+        for cell in &state.cells {
+            if let Cell::Number(n) = cell {
+                match numbers.get_mut((*n as usize).wrapping_sub(1)) {
+                    Some(seen) if !*seen => *seen = true,
+                    _ => return None,
+                }
+            }
+        }
+        if numbers.iter().all(|seen| *seen) {
+            Some(state)
+        } else {
+            None
+        }
+    }
+
+    pub fn size(&self) -> i8 {
+        self.size
+    }
+
+    /// All slots in row major order, including the border slots.
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    /// Whether every numbered tile is back where `PuzzleState::new` placed it.
+    pub fn is_solved(&self) -> bool {
+        (1..self.size + 1).all(|y| (1..self.size + 1).all(|x| self.get(x, y) == self.home(x, y)))
+    }
+
+    /// The numbered tile that belongs in the slot, counting in a wider type since the
+    /// numbers of large boards do not fit an `i8`.
+    fn home(&self, x: i8, y: i8) -> Cell {
+        let size = self.size as usize;
+        Cell::Number((x as usize + size * (y as usize - 1)) as u8)
+    }
+
+    fn is_valid_size(size: i8) -> bool {
+        size >= 1 && (size as usize) * (size as usize) <= u8::MAX as usize
+    }
+
+    /// Returns the content of the slot, treating anything outside the grid as vacant.
+    pub fn get(&self, x: i8, y: i8) -> Cell {
+        match self.index(x, y) {
+            Some(i) => self.cells[i],
+            None => Cell::Vacant,
+        }
+    }
+
+    /// Iterates over every occupied slot with its grid position.
+    pub fn tiles(&self) -> impl Iterator<Item = (Point<i8>, Cell)> + '_ {
+        let width = (self.size + 2) as usize;
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| **cell != Cell::Vacant)
+            .map(move |(i, cell)| {
+                let x = (i % width) as i8;
+                let y = (i / width) as i8;
+                (Point { x, y }, *cell)
+            })
+    }
+
+    /// Returns the legal shift of a row, or `None` if the row cannot be shifted at all.
+    pub fn row_shift(&self, row: i8) -> Option<Shift> {
+        if row < 1 || row > self.size {
+            return None;
+        }
+        let direction = if self.get(0, row) == Cell::Vacant {
+            Direction::West
+        } else {
+            Direction::East
+        };
+        Some(Shift {
+            idx: row as u8,
+            direction,
+        })
+    }
+
+    /// Returns the legal shift of a column, or `None` if the column cannot be shifted at all.
+    pub fn col_shift(&self, col: i8) -> Option<Shift> {
+        if col < 1 || col > self.size {
+            return None;
+        }
+        let direction = if self.get(col, 0) == Cell::Vacant {
+            Direction::North
+        } else {
+            Direction::South
+        };
+        Some(Shift {
+            idx: col as u8,
+            direction,
+        })
+    }
+
+    pub fn legal_shifts(&self) -> Vec<Shift> {
+        let rows = (1..self.size + 1).filter_map(|row| self.row_shift(row));
+        let cols = (1..self.size + 1).filter_map(|col| self.col_shift(col));
+        rows.chain(cols).collect()
+    }
+
+    pub fn is_legal(&self, shift: &Shift) -> bool {
+        let legal = if shift.is_row() {
+            self.row_shift(shift.idx as i8)
+        } else {
+            self.col_shift(shift.idx as i8)
+        };
+        legal == Some(*shift)
+    }
+
+    /// Applies the shift if it is legal, and returns whether the state has changed.
+    pub fn apply(&mut self, shift: &Shift) -> bool {
+        if !self.is_legal(shift) {
+            return false;
+        }
+
+        let idx = shift.idx as i8;
+        let last = self.size + 1;
+        match shift.direction {
+            Direction::East => {
+                for x in (0..last).rev() {
+                    self.set(x + 1, idx, self.get(x, idx));
+                }
+                self.set(0, idx, Cell::Vacant);
+            }
+            Direction::West => {
+                for x in 1..last + 1 {
+                    self.set(x - 1, idx, self.get(x, idx));
+                }
+                self.set(last, idx, Cell::Vacant);
+            }
+            Direction::South => {
+                for y in (0..last).rev() {
+                    self.set(idx, y + 1, self.get(idx, y));
+                }
+                self.set(idx, 0, Cell::Vacant);
+            }
+            Direction::North => {
+                for y in 1..last + 1 {
+                    self.set(idx, y - 1, self.get(idx, y));
+                }
+                self.set(idx, last, Cell::Vacant);
+            }
+        }
+        true
+    }
+
+    fn set(&mut self, x: i8, y: i8, cell: Cell) {
+        let i = self.index(x, y).expect("position should be on the grid");
+        self.cells[i] = cell;
+    }
+
+    fn index(&self, x: i8, y: i8) -> Option<usize> {
+        let width = self.size + 2;
+        if x < 0 || y < 0 || x >= width || y >= width {
+            None
+        } else {
+            Some((y as usize) * (width as usize) + x as usize)
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Drawing the board independently of where it ends up.
+//!
+//! Everything is drawn in board coordinates, `BOARD_SIZE` wide and high, which backends
+//! map to their output with a `Transform`. Text is always centred on the given point.
+use crate::board::BOARD_SIZE;
+use crate::utils::Point;
+
+/// Maps board coordinates to the output: scaled by `scale`, then moved by `offset`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    pub scale: Point<f64>,
+    pub offset: Point<f64>,
+}
+
+impl Transform {
+    /// Stretches the board to cover an output `width` by `height`.
+    pub fn stretch(width: f64, height: f64) -> Transform {
+        Transform {
+            scale: Point {
+                x: width / BOARD_SIZE,
+                y: height / BOARD_SIZE,
+            },
+            offset: Point { x: 0.0, y: 0.0 },
+        }
+    }
+
+    /// Fits the whole board into an output `width` by `height` without distorting it,
+    /// centred between equal margins on the longer side.
+    pub fn fit(width: f64, height: f64) -> Transform {
+        let scale = width.min(height) / BOARD_SIZE;
+        Transform {
+            scale: Point { x: scale, y: scale },
+            offset: Point {
+                x: 0.5 * (width - scale * BOARD_SIZE),
+                y: 0.5 * (height - scale * BOARD_SIZE),
+            },
+        }
+    }
+
+    /// The point of the board shown at `point` of the output.
+    pub fn to_board(&self, point: Point<f64>) -> Point<f64> {
+        Point {
+            x: (point.x - self.offset.x) / self.scale.x,
+            y: (point.y - self.offset.y) / self.scale.y,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    /// The radius of the rounded corners, or 0 for square ones
+    pub radius: f64,
+}
+
+impl Rect {
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+            radius: 0.0,
+        }
+    }
+
+    /// The same rectangle with rounded corners, limited to half of its shorter side.
+    pub fn rounded(self, radius: f64) -> Rect {
+        Rect {
+            radius: radius.max(0.0).min(0.5 * self.width.min(self.height)),
+            ..self
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextStyle<'a> {
+    /// The font size in board coordinates
+    pub size: f64,
+    pub colour: &'a str,
+    /// A CSS font family list
+    pub family: &'a str,
+    /// Whether to also trace the outline of the letters in the same colour
+    pub outline: bool,
+}
+
+pub trait Renderer {
+    /// Maps board coordinates to the output, replacing the previous transform.
+    fn set_transform(&mut self, transform: Transform);
+
+    /// The opacity of everything drawn afterwards.
+    fn set_alpha(&mut self, alpha: f64);
+
+    fn fill_rect(&mut self, rect: Rect, colour: &str);
+
+    fn stroke_rect(&mut self, rect: Rect, colour: &str, line_width: f64);
+
+    fn text(&mut self, text: &str, centre: Point<f64>, style: &TextStyle);
+
+    /// Draws straight lines through the points in turn.
+    fn polyline(&mut self, points: &[Point<f64>], colour: &str, line_width: f64);
+
+    /// Draws the part `source` of the picture into `dest`, if the renderer has a picture.
+    ///
+    /// `source` is given in fractions of the largest centred square of the picture, so that
+    /// `Rect::new(0.0, 0.0, 1.0, 1.0)` is the whole square.
+    fn picture(&mut self, source: Rect, dest: Rect);
+
+    /// Starts a group of shapes that belong together, such as the parts of a tile, drawn
+    /// relative to `offset` until the group ends. Groups are not nested.
+    fn begin_group(&mut self, class: &str, offset: Point<f64>);
+
+    fn end_group(&mut self);
+}
+
+/// A call made to a `RecordingRenderer`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrawCommand {
+    Transform(Transform),
+    Alpha(f64),
+    FillRect(Rect, String),
+    StrokeRect(Rect, String, f64),
+    Text {
+        text: String,
+        centre: Point<f64>,
+        size: f64,
+        colour: String,
+        family: String,
+    },
+    Polyline(Vec<Point<f64>>, String, f64),
+    Picture(Rect, Rect),
+    BeginGroup(String, Point<f64>),
+    EndGroup,
+}
+
+/// Remembers everything it is asked to draw, for checking the rendering without a browser.
+#[derive(Default)]
+pub struct RecordingRenderer {
+    pub commands: Vec<DrawCommand>,
+}
+
+impl RecordingRenderer {
+    /// The text drawn so far, in order.
+    pub fn texts(&self) -> Vec<&str> {
+        self.commands
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Renderer for RecordingRenderer {
+    fn set_transform(&mut self, transform: Transform) {
+        self.commands.push(DrawCommand::Transform(transform));
+    }
+
+    fn set_alpha(&mut self, alpha: f64) {
+        self.commands.push(DrawCommand::Alpha(alpha));
+    }
+
+    fn fill_rect(&mut self, rect: Rect, colour: &str) {
+        self.commands
+            .push(DrawCommand::FillRect(rect, colour.to_string()));
+    }
+
+    fn stroke_rect(&mut self, rect: Rect, colour: &str, line_width: f64) {
+        self.commands.push(DrawCommand::StrokeRect(
+            rect,
+            colour.to_string(),
+            line_width,
+        ));
+    }
+
+    fn text(&mut self, text: &str, centre: Point<f64>, style: &TextStyle) {
+        self.commands.push(DrawCommand::Text {
+            text: text.to_string(),
+            centre,
+            size: style.size,
+            colour: style.colour.to_string(),
+            family: style.family.to_string(),
+        });
+    }
+
+    fn polyline(&mut self, points: &[Point<f64>], colour: &str, line_width: f64) {
+        self.commands.push(DrawCommand::Polyline(
+            points.to_vec(),
+            colour.to_string(),
+            line_width,
+        ));
+    }
+
+    fn picture(&mut self, source: Rect, dest: Rect) {
+        self.commands.push(DrawCommand::Picture(source, dest));
+    }
+
+    fn begin_group(&mut self, class: &str, offset: Point<f64>) {
+        self.commands
+            .push(DrawCommand::BeginGroup(class.to_string(), offset));
+    }
+
+    fn end_group(&mut self) {
+        self.commands.push(DrawCommand::EndGroup);
+    }
+}
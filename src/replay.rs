@@ -0,0 +1,190 @@
+//! Recording the moves of a game with their timestamps, and playing them back.
+use crate::code;
+use crate::puzzle::{PuzzleState, Shift};
+use serde::{Deserialize, Serialize};
+
+/// A starting position and the moves made from it, exported as JSON like
+/// `{"start":"DAAAASMEVgeJ","moves":[{"time":1520.5,"move":"R2>"}]}`. Moves that do not
+/// count as the player's, such as undos and the solver's, are marked `"counted":false`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    /// The position code of the starting position
+    pub start: String,
+    pub moves: Vec<ReplayMove>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplayMove {
+    /// Milliseconds since the start of the game
+    pub time: f64,
+    #[serde(rename = "move", with = "notation")]
+    pub shift: Shift,
+    /// Whether the player made the move, rather than undoing one or letting the solver play
+    #[serde(default = "counted_by_default", skip_serializing_if = "is_counted")]
+    pub counted: bool,
+}
+
+fn counted_by_default() -> bool {
+    true
+}
+
+fn is_counted(counted: &bool) -> bool {
+    *counted
+}
+
+impl Replay {
+    pub fn new(start: &PuzzleState) -> Replay {
+        Replay {
+            start: code::encode(start),
+            moves: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, time: f64, shift: Shift, counted: bool) {
+        self.moves.push(ReplayMove {
+            time,
+            shift,
+            counted,
+        });
+    }
+
+    pub fn from_json(json: &str) -> Result<Replay, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("replays should serialize")
+    }
+
+    /// The time of the last move.
+    pub fn duration(&self) -> f64 {
+        self.moves.last().map_or(0.0, |m| m.time)
+    }
+}
+
+/// Moves are written in move notation rather than as structs.
+mod notation {
+    use crate::puzzle::Shift;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(shift: &Shift, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(shift)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Shift, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Plays back a replay at an adjustable speed, telling which moves are due as time passes.
+pub struct Playback {
+    replay: Replay,
+    start: PuzzleState,
+    /// The index of the next move to be played
+    next: usize,
+    time: f64,
+    speed: f64,
+    paused: bool,
+}
+
+impl Playback {
+    /// Returns `None` unless the starting position is valid, the moves are in chronological
+    /// order and every move is legal in turn.
+    pub fn new(replay: Replay) -> Option<Playback> {
+        let start = code::decode(&replay.start)?;
+        let mut state = start.clone();
+        let mut last_time = 0.0;
+        for m in &replay.moves {
+            if m.time.is_nan() || m.time < last_time || !state.apply(&m.shift) {
+                return None;
+            }
+            last_time = m.time;
+        }
+
+        Some(Playback {
+            replay,
+            start,
+            next: 0,
+            time: 0.0,
+            speed: 1.0,
+            paused: false,
+        })
+    }
+
+    pub fn start(&self) -> &PuzzleState {
+        &self.start
+    }
+
+    /// Advances the playback by `dt` milliseconds of real time, returning the moves that are due.
+    pub fn advance(&mut self, dt: f64) -> Vec<Shift> {
+        if self.paused {
+            return Vec::new();
+        }
+        self.time = (self.time + dt * self.speed).min(self.duration());
+
+        let due = self.replay.moves[self.next..]
+            .iter()
+            .take_while(|m| m.time <= self.time)
+            .map(|m| m.shift)
+            .collect::<Vec<_>>();
+        self.next += due.len();
+        due
+    }
+
+    /// Jumps to the given time, returning the position after every move made up to then.
+    pub fn seek(&mut self, time: f64) -> PuzzleState {
+        self.time = time.max(0.0).min(self.duration());
+        self.next = self
+            .replay
+            .moves
+            .iter()
+            .take_while(|m| m.time <= self.time)
+            .count();
+
+        let mut state = self.start.clone();
+        for m in &self.replay.moves[..self.next] {
+            state.apply(&m.shift);
+        }
+        state
+    }
+
+    /// The current time in the replay, in milliseconds.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    pub fn duration(&self) -> f64 {
+        self.replay.duration()
+    }
+
+    /// The number of moves played so far.
+    pub fn played(&self) -> usize {
+        self.next
+    }
+
+    /// The number of moves played so far that count as the player's.
+    pub fn counted(&self) -> usize {
+        self.replay.moves[..self.next]
+            .iter()
+            .filter(|m| m.counted)
+            .count()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next == self.replay.moves.len()
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// How many times faster than the original the replay is played, at least a tenth.
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = speed.max(0.1);
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+}
@@ -0,0 +1,46 @@
+/// A source of uniformly distributed random numbers.
+pub trait Rng {
+    fn next_u64(&mut self) -> u64;
+
+    /// Returns a random number in the range [0, 1).
+    fn random(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// A deterministic SplitMix64 generator, producing the same sequence for the same seed.
+#[derive(Clone, Debug)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> SeededRng {
+        SeededRng { state: seed }
+    }
+}
+
+impl Rng for SeededRng {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Picks a fresh seed from `Math.random`, for games that were not given one.
+#[cfg(target_arch = "wasm32")]
+pub fn random_seed() -> u32 {
+    (js_sys::Math::random() * u32::MAX as f64) as u32
+}
+
+/// Picks a fresh seed from the clock, for games that were not given one.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn random_seed() -> u32 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.subsec_nanos() ^ time.as_secs() as u32)
+}
@@ -0,0 +1,49 @@
+use crate::history::History;
+use crate::puzzle::{Cell, PuzzleState, Shift};
+use crate::stats::Stats;
+use crate::utils::{log, window};
+use serde::{Deserialize, Serialize};
+
+/// Everything needed to resume a game after the page has been closed.
+#[derive(Serialize, Deserialize)]
+pub struct SavedGame {
+    pub seed: u32,
+    pub size: i8,
+    pub cells: Vec<Cell>,
+    pub stats: Stats,
+    pub history: History,
+    /// The shifts that made the position from the solved layout, missing from older saves
+    #[serde(default)]
+    pub trail: Option<Vec<Shift>>,
+}
+
+impl SavedGame {
+    pub fn state(&self) -> Option<PuzzleState> {
+        PuzzleState::from_cells(self.size, self.cells.clone())
+    }
+}
+
+/// Local storage is missing when it is disabled, and access to it throws in some private modes.
+fn local_storage() -> Option<web_sys::Storage> {
+    window().local_storage().ok().flatten()
+}
+
+pub fn load(key: &str) -> Option<SavedGame> {
+    let json = local_storage()?.get_item(key).ok()??;
+    match serde_json::from_str(&json) {
+        Ok(saved) => Some(saved),
+        Err(e) => {
+            log(&format!("Ignoring unreadable saved game: {}", e));
+            None
+        }
+    }
+}
+
+pub fn store(key: &str, game: &SavedGame) {
+    if let Some(storage) = local_storage() {
+        let json = serde_json::to_string(game).expect("saved games should serialize");
+        if storage.set_item(key, &json).is_err() {
+            log("Could not save the game");
+        }
+    }
+}
@@ -1,18 +1,186 @@
-use crate::utils::Direction;
-use js_sys::Math::random;
-use std::collections::VecDeque;
+use crate::puzzle::{PuzzleState, Shift};
+use crate::rng::Rng;
+use crate::solver::{solve_within, SEARCH_BUDGET};
+use crate::utils::{log, Direction};
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
 
-pub fn shuffle(grid_size: i8, steps: i8) -> VecDeque<ShiftAction> {
-    let mut actions: VecDeque<ShiftAction> = VecDeque::new();
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    /// The range of optimal solution lengths a scramble of this difficulty must fall into.
+    pub fn solution_lengths(&self) -> RangeInclusive<usize> {
+        match self {
+            Difficulty::Easy => 3..=5,
+            Difficulty::Medium => 6..=8,
+            Difficulty::Hard => 9..=11,
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    /// Parses the lowercase name of a difficulty, such as `medium`.
+    fn from_str(name: &str) -> Result<Difficulty, String> {
+        match name {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(format!("Unknown difficulty {}", name)),
+        }
+    }
+}
+
+/// How many random walks a scramble tries before settling for the furthest position found.
+const SCRAMBLE_ATTEMPTS: usize = 20;
+
+/// How many positions a scramble may expand with the solver in total, across all its walks.
+const SCRAMBLE_BUDGET: usize = 4 * SEARCH_BUDGET;
+
+/// Scrambles the board so that its shortest solution is within the difficulty's range.
+///
+/// Random walks are taken from the solved layout, and every position along the way is
+/// checked with the solver until one of them is at least as far from solved as a target
+/// picked from the range. Since a walk never exceeds the upper end of the range, the
+/// shortest solution cannot either.
+///
+/// Small boards may have no positions that far from solved, and large boards may be too
+/// expensive to check, so the walks and the solver are bounded. When they run out, the
+/// furthest unsolved position that was checked is used instead, or the last walk if none
+/// could be.
+pub fn scramble(grid_size: i8, difficulty: Difficulty, rng: &mut impl Rng) -> Vec<Shift> {
+    let lengths = difficulty.solution_lengths();
+    let target =
+        *lengths.start() + randint(rng, 0, (lengths.end() - lengths.start() + 1) as u8) as usize;
+
+    let mut budget = SCRAMBLE_BUDGET;
+    let mut furthest: Option<(usize, Vec<Shift>)> = None;
+    let mut shifts: Vec<Shift> = Vec::new();
+    for _ in 0..SCRAMBLE_ATTEMPTS {
+        let mut state = PuzzleState::new(grid_size);
+        shifts.clear();
+
+        while shifts.len() < *lengths.end() {
+            // Do not immediately undo the previous shift
+            let candidates: Vec<_> = state
+                .legal_shifts()
+                .into_iter()
+                .filter(|s| Some(s.inverse()) != shifts.last().copied())
+                .collect();
+            let shift = candidates[randint(rng, 0, candidates.len() as u8) as usize];
+            state.apply(&shift);
+            shifts.push(shift);
+
+            if shifts.len() >= target {
+                let solution = match solve_within(&state, *lengths.end(), budget) {
+                    Ok(solution) => solution,
+                    Err(_) => return fall_back(grid_size, furthest, shifts, difficulty, target),
+                };
+                budget -= solution.expanded;
+                if solution.shifts.len() >= target {
+                    return shifts;
+                }
+                // A walk that has come back to the solved layout is no scramble at all
+                let unsolved = !solution.shifts.is_empty();
+                if unsolved
+                    && furthest
+                        .as_ref()
+                        .is_none_or(|(length, _)| solution.shifts.len() > *length)
+                {
+                    furthest = Some((solution.shifts.len(), shifts.clone()));
+                }
+            }
+        }
+    }
+    fall_back(grid_size, furthest, shifts, difficulty, target)
+}
+
+/// The furthest unsolved position checked, or the last walk if none could be, logging that
+/// the scramble is easier than its difficulty or unchecked. A walk that happens to leave
+/// the board solved is taken one shift further.
+fn fall_back(
+    grid_size: i8,
+    furthest: Option<(usize, Vec<Shift>)>,
+    mut walk: Vec<Shift>,
+    difficulty: Difficulty,
+    target: usize,
+) -> Vec<Shift> {
+    match furthest {
+        Some((length, shifts)) => {
+            log(&format!(
+                "Scrambled {} with a shortest solution of {} shifts instead of {}",
+                difficulty, length, target
+            ));
+            shifts
+        }
+        None => {
+            let mut state = PuzzleState::new(grid_size);
+            for shift in &walk {
+                state.apply(shift);
+            }
+            // Any shift moves some tiles away from home
+            if state.is_solved() {
+                walk.push(state.legal_shifts()[0]);
+            }
+            log(&format!(
+                "Scrambled {} with an unchecked walk of {} shifts",
+                difficulty,
+                walk.len()
+            ));
+            walk
+        }
+    }
+}
+
+/// Shifts `steps` random lines, then arranges every row west and every column north.
+///
+/// The shifts can cancel each other out, so while they would leave the board solved,
+/// more random lines are shifted until they do not.
+pub fn shuffle(grid_size: i8, steps: i8, rng: &mut impl Rng) -> Vec<Shift> {
+    let mut shifts: Vec<Shift> = Vec::new();
 
     let mut horizontal_state = vec![false; grid_size as usize];
     let mut vertical_state = vec![false; grid_size as usize];
     let mut last_idx: u8 = 0;
 
-    for _ in 0..steps {
-        let is_row = random() > 0.5;
+    let mut taken = 0;
+    loop {
+        if taken >= steps {
+            let arranged = arrange(&horizontal_state, &vertical_state);
+            let mut state = PuzzleState::new(grid_size);
+            for shift in shifts.iter().chain(&arranged) {
+                state.apply(shift);
+            }
+            if !state.is_solved() {
+                shifts.extend(arranged);
+                return shifts;
+            }
+        }
+        taken += 1;
+
+        let is_row = rng.random() > 0.5;
         let idx: u8 = {
-            let r = randint(1, grid_size as u8);
+            let r = randint(rng, 1, grid_size as u8);
             // Ensure that we are not picking the same index as last time
             if r >= last_idx {
                 r + 1
@@ -41,43 +209,33 @@ pub fn shuffle(grid_size: i8, steps: i8) -> VecDeque<ShiftAction> {
             }
         };
 
-        actions.push_back(ShiftAction::create(idx, direction));
+        shifts.push(Shift { idx, direction });
     }
+}
 
-    // Arrange everything north/west to make the initial board prettier
+/// The shifts arranging everything north/west to make the initial board prettier.
+fn arrange(horizontal_state: &[bool], vertical_state: &[bool]) -> Vec<Shift> {
+    let mut shifts = Vec::new();
     for (idx, is_east) in horizontal_state.iter().enumerate() {
         if *is_east {
-            actions.push_back(ShiftAction::create((idx + 1) as u8, Direction::West));
+            shifts.push(Shift {
+                idx: (idx + 1) as u8,
+                direction: Direction::West,
+            });
         }
     }
     for (idx, is_south) in vertical_state.iter().enumerate() {
         if *is_south {
-            actions.push_back(ShiftAction::create((idx + 1) as u8, Direction::North));
-        }
-    }
-
-    actions
-}
-
-pub struct ShiftAction {
-    pub current_offset: f64,
-    pub remaining_time: f64,
-    pub idx: u8,
-    pub direction: Direction,
-}
-
-impl ShiftAction {
-    fn create(idx: u8, direction: Direction) -> ShiftAction {
-        ShiftAction {
-            current_offset: 0.0,
-            remaining_time: 500.0,
-            idx: idx,
-            direction: direction,
+            shifts.push(Shift {
+                idx: (idx + 1) as u8,
+                direction: Direction::North,
+            });
         }
     }
+    shifts
 }
 
-fn randint(from: u8, to: u8) -> u8 {
-    let r = ((to - from) as f64 * random()) + from as f64;
+fn randint(rng: &mut impl Rng, from: u8, to: u8) -> u8 {
+    let r = ((to - from) as f64 * rng.random()) + from as f64;
     r as u8
 }
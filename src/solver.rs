@@ -0,0 +1,475 @@
+use crate::puzzle::{Cell, PuzzleState, Shift};
+use std::cmp::Reverse;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+
+/// A sequence of shifts that solves a position, which is a shortest one unless it was
+/// found by `solve_greedy` or `retrace`.
+#[derive(Clone, Debug)]
+pub struct Solution {
+    pub shifts: Vec<Shift>,
+    /// The number of positions the search has expanded to find the solution, or generated
+    /// for `solve_greedy`
+    pub expanded: usize,
+}
+
+/// Why a bounded search gave up without a solution.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SearchFailure {
+    /// There is no solution within the maximum depth
+    Exhausted { expanded: usize },
+    /// The search expanded as many positions as it was allowed to before finding one
+    OutOfBudget { expanded: usize },
+}
+
+impl SearchFailure {
+    /// The number of positions the search expanded before giving up
+    pub fn expanded(&self) -> usize {
+        match *self {
+            SearchFailure::Exhausted { expanded } | SearchFailure::OutOfBudget { expanded } => {
+                expanded
+            }
+        }
+    }
+}
+
+/// The longest solution the game searches for.
+pub const MAX_DEPTH: usize = 20;
+
+/// How many positions the game expands in a single search before giving up, or generates
+/// in `solve_greedy`, which keeps each search to a few hundred milliseconds natively even
+/// on the largest boards.
+pub const SEARCH_BUDGET: usize = 250_000;
+
+/// Finds a shortest sequence of shifts that brings the state back to its solved layout.
+///
+/// Returns `None` if the position cannot be solved within `max_depth` shifts. The search
+/// is not bounded otherwise, so deep positions on large boards can take very long.
+pub fn solve(state: &PuzzleState, max_depth: usize) -> Option<Solution> {
+    solve_ida(state, max_depth)
+}
+
+/// Like `solve`, but also gives up once `budget` positions have been expanded, telling
+/// which limit stopped the search.
+pub fn solve_within(
+    state: &PuzzleState,
+    max_depth: usize,
+    budget: usize,
+) -> Result<Solution, SearchFailure> {
+    ida(state, max_depth, budget)
+}
+
+/// Exhaustive breadth first search remembering the shift that first reached every visited position.
+///
+/// Even the 3x3 board has tens of millions of reachable positions, so this is only practical
+/// for shallow positions. It is kept as a reference to check `solve_ida` against.
+pub fn solve_bfs(state: &PuzzleState, max_depth: usize) -> Option<Solution> {
+    let mut visited: HashMap<PuzzleState, Option<Shift>> = HashMap::new();
+    let mut queue = VecDeque::new();
+    visited.insert(state.clone(), None);
+    queue.push_back((state.clone(), 0));
+
+    let mut expanded = 0;
+    while let Some((current, depth)) = queue.pop_front() {
+        expanded += 1;
+        if current.is_solved() {
+            return Some(Solution {
+                shifts: backtrack(&visited, current),
+                expanded,
+            });
+        }
+        if depth == max_depth {
+            continue;
+        }
+
+        for shift in current.legal_shifts() {
+            let mut next = current.clone();
+            next.apply(&shift);
+            if !visited.contains_key(&next) {
+                visited.insert(next.clone(), Some(shift));
+                queue.push_back((next, depth + 1));
+            }
+        }
+    }
+
+    None
+}
+
+fn backtrack(visited: &HashMap<PuzzleState, Option<Shift>>, mut state: PuzzleState) -> Vec<Shift> {
+    let mut shifts = Vec::new();
+    while let Some(Some(shift)) = visited.get(&state) {
+        shifts.push(*shift);
+        state.apply(&shift.inverse());
+    }
+    shifts.reverse();
+    shifts
+}
+
+/// Iterative deepening A* search using `heuristic` as the lower bound on the remaining shifts.
+pub fn solve_ida(state: &PuzzleState, max_depth: usize) -> Option<Solution> {
+    ida(state, max_depth, usize::MAX).ok()
+}
+
+fn ida(state: &PuzzleState, max_depth: usize, budget: usize) -> Result<Solution, SearchFailure> {
+    let mut search = IdaSearch {
+        state: state.clone(),
+        path: Vec::new(),
+        expanded: 0,
+        budget,
+    };
+
+    let mut bound = heuristic(state);
+    while bound <= max_depth {
+        match search.search(0, bound) {
+            Bound::Found => {
+                return Ok(Solution {
+                    shifts: search.path,
+                    expanded: search.expanded,
+                })
+            }
+            Bound::Exceeded(next) => bound = next,
+            Bound::Exhausted => break,
+            Bound::OutOfBudget => {
+                return Err(SearchFailure::OutOfBudget {
+                    expanded: search.expanded,
+                })
+            }
+        }
+    }
+
+    Err(SearchFailure::Exhausted {
+        expanded: search.expanded,
+    })
+}
+
+enum Bound {
+    Found,
+    /// The smallest estimate that exceeded the current bound
+    Exceeded(usize),
+    Exhausted,
+    /// The search expanded as many positions as it was allowed to
+    OutOfBudget,
+}
+
+struct IdaSearch {
+    state: PuzzleState,
+    path: Vec<Shift>,
+    expanded: usize,
+    budget: usize,
+}
+
+impl IdaSearch {
+    fn search(&mut self, depth: usize, bound: usize) -> Bound {
+        if self.expanded >= self.budget {
+            return Bound::OutOfBudget;
+        }
+        self.expanded += 1;
+        let estimate = depth + heuristic(&self.state);
+        if estimate > bound {
+            return Bound::Exceeded(estimate);
+        }
+        if self.state.is_solved() {
+            return Bound::Found;
+        }
+
+        let mut result = Bound::Exhausted;
+        for shift in self.state.legal_shifts() {
+            if !self.is_canonical(&shift) {
+                continue;
+            }
+
+            self.state.apply(&shift);
+            self.path.push(shift);
+            match self.search(depth + 1, bound) {
+                Bound::Found => return Bound::Found,
+                Bound::OutOfBudget => return Bound::OutOfBudget,
+                Bound::Exceeded(next) => {
+                    result = match result {
+                        Bound::Exceeded(current) => Bound::Exceeded(current.min(next)),
+                        _ => Bound::Exceeded(next),
+                    }
+                }
+                Bound::Exhausted => (),
+            }
+            self.path.pop();
+            self.state.apply(&shift.inverse());
+        }
+
+        result
+    }
+
+    /// Shifts of different rows (or different columns) commute, so consecutive shifts
+    /// of the same kind are only explored in increasing index order. This also rules
+    /// out undoing the previous shift.
+    fn is_canonical(&self, shift: &Shift) -> bool {
+        match self.path.last() {
+            Some(last) if last.is_row() == shift.is_row() => shift.idx > last.idx,
+            _ => true,
+        }
+    }
+}
+
+/// A lower bound on the number of shifts needed to solve the state.
+///
+/// A row shift moves at most `size + 1` tiles one slot horizontally, and a column
+/// shift moves at most `size + 1` tiles one slot vertically, so the total horizontal
+/// and vertical distances of the numbered tiles from home bound the number of row and
+/// column shifts respectively. Neither can be less than the largest distance of a single tile.
+pub fn heuristic(state: &PuzzleState) -> usize {
+    let line = state.size() as usize + 1;
+    let (horizontal, vertical) = distances(state);
+    horizontal.min_shifts(line) + vertical.min_shifts(line)
+}
+
+/// Finds some solution quickly, though not necessarily a shortest one, for when
+/// `solve_within` runs out of budget.
+///
+/// Best first searches weigh the shifts made less and less against the distance left,
+/// each with a third of the budget, so that a position that is hard to solve well still
+/// gets a long solution. The first one usually finds a solution a few shifts longer than a
+/// shortest one. Gives up once `budget` positions have been generated in total, counting
+/// every successor of the positions expanded.
+pub fn solve_greedy(state: &PuzzleState, budget: usize) -> Option<Solution> {
+    let line = state.size() as usize + 1;
+    let mut generated = 0;
+    for (i, shift_cost) in [line, 1, 0].iter().enumerate() {
+        let share = (budget - generated) / (3 - i);
+        match best_first(state, share, *shift_cost) {
+            Ok(mut solution) => {
+                solution.expanded += generated;
+                return Some(solution);
+            }
+            Err(spent) => generated += spent,
+        }
+    }
+    None
+}
+
+/// The longest solution the game plays back when auto-solving, since a greedy one can
+/// take minutes to watch.
+pub const MAX_AUTO_SOLVE_LENGTH: usize = 40;
+
+/// How many positions the game expands looking for a shorter solution than `retrace`
+/// gives, which is less than `SEARCH_BUDGET` since there is a solution either way.
+pub const RETRACE_SEARCH_BUDGET: usize = SEARCH_BUDGET / 10;
+
+/// Solves the position for a hint or auto-solve.
+///
+/// With the `trail` of shifts that made the position from the solved layout, this is a
+/// shortest solution if one shorter than undoing the trail is found within
+/// `RETRACE_SEARCH_BUDGET`, and the trail undone otherwise, so that boards of any size are
+/// solved. Without one, it is a shortest solution if one is found within `SEARCH_BUDGET`,
+/// otherwise any solution `solve_greedy` finds within the same budget, which is rarely
+/// the case on boards of 5 or more rows.
+pub fn solve_for_game(state: &PuzzleState, trail: Option<&[Shift]>) -> Option<Solution> {
+    match trail.and_then(|trail| retrace(state, trail)) {
+        Some(retraced) => {
+            let shorter = retraced.len().saturating_sub(1).min(MAX_DEPTH);
+            match solve_within(state, shorter, RETRACE_SEARCH_BUDGET) {
+                Ok(solution) => Some(solution),
+                Err(failure) => Some(Solution {
+                    shifts: retraced,
+                    expanded: failure.expanded(),
+                }),
+            }
+        }
+        None => solve_within(state, MAX_DEPTH, SEARCH_BUDGET)
+            .ok()
+            .or_else(|| solve_greedy(state, SEARCH_BUDGET)),
+    }
+}
+
+/// Undoes the `trail` of shifts that made the position from the solved layout, or returns
+/// `None` if they do not lead back to it.
+///
+/// Shifts of different rows (or different columns) commute, and shifting the same line
+/// twice in a row undoes it, so shifts of the same kind that follow each other are undone
+/// together as a set of lines, dropping the lines shifted an even number of times.
+pub fn retrace(state: &PuzzleState, trail: &[Shift]) -> Option<Vec<Shift>> {
+    let mut groups: Vec<(bool, Vec<u8>)> = Vec::new();
+    for shift in trail.iter().rev() {
+        match groups.last_mut() {
+            Some((is_row, lines)) if *is_row == shift.is_row() => {
+                match lines.iter().position(|idx| *idx == shift.idx) {
+                    Some(i) => {
+                        lines.remove(i);
+                    }
+                    None => lines.push(shift.idx),
+                }
+                // The groups either side of an emptied one follow each other
+                if lines.is_empty() {
+                    groups.pop();
+                }
+            }
+            _ => groups.push((shift.is_row(), vec![shift.idx])),
+        }
+    }
+
+    let mut current = state.clone();
+    let mut shifts = Vec::new();
+    for (is_row, mut lines) in groups {
+        lines.sort_unstable();
+        for idx in lines {
+            let shift = if is_row {
+                current.row_shift(idx as i8)
+            } else {
+                current.col_shift(idx as i8)
+            }?;
+            current.apply(&shift);
+            shifts.push(shift);
+        }
+    }
+    if current.is_solved() {
+        Some(shifts)
+    } else {
+        None
+    }
+}
+
+/// A position found by `best_first`, with the shift that first reached it from its parent.
+struct Node {
+    key: Box<[u8]>,
+    depth: usize,
+    parent: usize,
+    shift: Option<Shift>,
+}
+
+/// Best first search ordered by the distance left plus `shift_cost` for every shift made,
+/// measured in tile slots. Returns the number of positions generated if it gives up.
+///
+/// Positions are kept as one byte per slot, and visited positions only by the hash of
+/// those bytes. A collision merely skips a position, so the search may miss a solution
+/// but never returns a wrong one.
+fn best_first(state: &PuzzleState, budget: usize, shift_cost: usize) -> Result<Solution, usize> {
+    let size = state.size();
+    let priority = |depth: usize, state: &PuzzleState| depth * shift_cost + 2 * remaining(state);
+
+    // The queue holds the priority and index of the positions found, and ties go to the
+    // position found first, which keeps solutions shorter
+    let mut found = vec![Node {
+        key: to_key(state),
+        depth: 0,
+        parent: 0,
+        shift: None,
+    }];
+    let mut visited = HashSet::new();
+    visited.insert(hash_key(&found[0].key));
+    let mut queue = BinaryHeap::new();
+    queue.push(Reverse((priority(0, state), 0)));
+
+    let mut generated = 1;
+    while let Some(Reverse((_, index))) = queue.pop() {
+        let current = from_key(size, &found[index].key);
+        if current.is_solved() {
+            return Ok(Solution {
+                shifts: path_to(&found, index),
+                expanded: generated,
+            });
+        }
+
+        let depth = found[index].depth + 1;
+        for shift in current.legal_shifts() {
+            if generated >= budget {
+                return Err(generated);
+            }
+            generated += 1;
+            let mut next = current.clone();
+            next.apply(&shift);
+            let key = to_key(&next);
+            if visited.insert(hash_key(&key)) {
+                queue.push(Reverse((priority(depth, &next), found.len())));
+                found.push(Node {
+                    key,
+                    depth,
+                    parent: index,
+                    shift: Some(shift),
+                });
+            }
+        }
+    }
+
+    Err(generated)
+}
+
+fn path_to(found: &[Node], mut index: usize) -> Vec<Shift> {
+    let mut shifts = Vec::new();
+    while let Some(shift) = found[index].shift {
+        shifts.push(shift);
+        index = found[index].parent;
+    }
+    shifts.reverse();
+    shifts
+}
+
+fn to_key(state: &PuzzleState) -> Box<[u8]> {
+    state
+        .cells()
+        .iter()
+        .map(|cell| match cell {
+            Cell::Vacant => 0,
+            Cell::Number(n) => *n,
+            Cell::Empty => u8::MAX,
+        })
+        .collect()
+}
+
+fn from_key(size: i8, key: &[u8]) -> PuzzleState {
+    let cells = key
+        .iter()
+        .map(|byte| match *byte {
+            0 => Cell::Vacant,
+            u8::MAX => Cell::Empty,
+            n => Cell::Number(n),
+        })
+        .collect();
+    PuzzleState::from_cells(size, cells).expect("keys should only be made from valid states")
+}
+
+fn hash_key(key: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// How far the tiles are from home in slots: their total distance, plus the `heuristic`
+/// counted as whole lines so that positions needing fewer shifts come first.
+fn remaining(state: &PuzzleState) -> usize {
+    let line = state.size() as usize + 1;
+    let (horizontal, vertical) = distances(state);
+    let heuristic = horizontal.min_shifts(line) + vertical.min_shifts(line);
+    horizontal.total + vertical.total + heuristic * line
+}
+
+/// The horizontal and vertical distances of the numbered tiles from home.
+fn distances(state: &PuzzleState) -> (Distance, Distance) {
+    let size = state.size() as usize;
+    let mut horizontal = Distance::default();
+    let mut vertical = Distance::default();
+    for (position, cell) in state.tiles() {
+        if let Cell::Number(n) = cell {
+            horizontal.add(position.x as usize, (n as usize - 1) % size + 1);
+            vertical.add(position.y as usize, (n as usize - 1) / size + 1);
+        }
+    }
+    (horizontal, vertical)
+}
+
+#[derive(Default)]
+struct Distance {
+    total: usize,
+    max: usize,
+}
+
+impl Distance {
+    fn add(&mut self, position: usize, home: usize) {
+        let distance = position.max(home) - position.min(home);
+        self.total += distance;
+        self.max = self.max.max(distance);
+    }
+
+    /// Every shift moves a tile by at most one slot, and moves at most `per_shift` tiles.
+    fn min_shifts(&self, per_shift: usize) -> usize {
+        self.max.max(self.total.div_ceil(per_shift))
+    }
+}
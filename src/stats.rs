@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+/// Counts the moves of the player and times how long they take to solve the puzzle.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Stats {
+    moves: u32,
+    elapsed: f64,
+    running: bool,
+    finished: bool,
+}
+
+impl Stats {
+    /// Stats showing the given progress, e.g. while playing back a replay.
+    pub fn at(moves: u32, elapsed: f64) -> Stats {
+        Stats {
+            moves,
+            elapsed,
+            ..Stats::default()
+        }
+    }
+
+    /// Counts a completed shift, starting the timer on the first one.
+    pub fn record_move(&mut self) {
+        if !self.finished {
+            self.moves += 1;
+            self.running = true;
+        }
+    }
+
+    pub fn tick(&mut self, dt: f64) {
+        if self.running {
+            self.elapsed += dt;
+        }
+    }
+
+    /// Stops the timer without finishing the game, e.g. when the solver takes over.
+    pub fn pause(&mut self) {
+        self.running = false;
+    }
+
+    /// Stops the timer and the move count once the player has solved the puzzle.
+    pub fn stop(&mut self) {
+        self.running = false;
+        self.finished = true;
+    }
+
+    pub fn moves(&self) -> u32 {
+        self.moves
+    }
+
+    /// The time spent playing, in milliseconds.
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// The elapsed time formatted as minutes and seconds.
+    pub fn clock(&self) -> String {
+        let seconds = (self.elapsed / 1000.0) as u64;
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
@@ -0,0 +1,135 @@
+use crate::canvas::{CanvasRenderer, Picture};
+use crate::renderer::{Renderer, Transform};
+use crate::svg::SvgRenderer;
+use crate::utils::log;
+use wasm_bindgen::JsCast;
+
+/// Where the game is rendered, which is also the element receiving the pointer input.
+pub enum Surface {
+    Canvas(CanvasRenderer),
+    Svg(Box<SvgSurface>),
+}
+
+/// An SVG document in a container, built once and then only rebuilt when its layout
+/// changes, so that frames in between only update transforms and text.
+pub struct SvgSurface {
+    container: web_sys::HtmlElement,
+    renderer: SvgRenderer,
+    /// The layout of the document shown
+    layout: String,
+    /// The `<g>` elements shown with their transforms, in drawing order
+    groups: Vec<(web_sys::Element, String)>,
+    /// The `<text>` elements shown with their content, in drawing order
+    texts: Vec<(web_sys::Element, String)>,
+}
+
+impl Surface {
+    pub fn svg(container: web_sys::HtmlElement) -> Surface {
+        Surface::Svg(Box::new(SvgSurface {
+            container,
+            renderer: SvgRenderer::new(),
+            layout: String::new(),
+            groups: Vec::new(),
+            texts: Vec::new(),
+        }))
+    }
+
+    pub fn element(&self) -> web_sys::HtmlElement {
+        match self {
+            Surface::Canvas(renderer) => renderer.canvas().unchecked_into(),
+            Surface::Svg(svg) => svg.container.clone(),
+        }
+    }
+
+    /// Maps board coordinates to the element's box: a canvas is stretched over it, and an
+    /// SVG document is fitted into it.
+    pub fn transform(&self) -> Transform {
+        let rect = self.element().get_bounding_client_rect();
+        match self {
+            Surface::Canvas(_) => Transform::stretch(rect.width(), rect.height()),
+            Surface::Svg(_) => Transform::fit(rect.width(), rect.height()),
+        }
+    }
+
+    /// Cuts the tiles from the picture, or goes back to plain tiles without one.
+    /// Returns whether the picture can be shown.
+    pub fn set_picture(&mut self, picture: Option<Picture>) -> bool {
+        match self {
+            Surface::Canvas(renderer) => {
+                let shown = picture.is_some();
+                renderer.set_picture(picture);
+                shown
+            }
+            Surface::Svg(svg) => {
+                let url = match picture {
+                    Some(Picture::Image(image)) => Some(image.src()),
+                    Some(Picture::Bitmap(_)) => {
+                        log("Image bitmaps cannot be shown in SVG, use an image element instead");
+                        None
+                    }
+                    None => None,
+                };
+                let shown = url.is_some();
+                svg.renderer.set_picture(url);
+                shown
+            }
+        }
+    }
+
+    /// Renders a frame with `draw`, scaled to fill the surface.
+    pub fn draw(&mut self, draw: impl FnOnce(&mut dyn Renderer)) {
+        match self {
+            Surface::Canvas(renderer) => {
+                let canvas = renderer.canvas();
+                renderer.set_transform(Transform::stretch(
+                    canvas.width() as f64,
+                    canvas.height() as f64,
+                ));
+                draw(renderer);
+            }
+            Surface::Svg(svg) => svg.draw(draw),
+        }
+    }
+}
+
+impl SvgSurface {
+    fn draw(&mut self, draw: impl FnOnce(&mut dyn Renderer)) {
+        let renderer = &mut self.renderer;
+        renderer.clear();
+        draw(renderer);
+        if renderer.layout() != self.layout {
+            self.container.set_inner_html(&renderer.to_svg());
+            self.layout = renderer.layout().to_string();
+            self.groups = shown_elements(&self.container, "g", renderer.transforms());
+            self.texts = shown_elements(&self.container, "text", renderer.texts());
+            return;
+        }
+
+        // Only the transforms and text can have changed, such as while tiles shift
+        for ((group, shown), transform) in self.groups.iter_mut().zip(renderer.transforms()) {
+            if shown != transform {
+                group.set_attribute("transform", transform).unwrap();
+                *shown = transform.clone();
+            }
+        }
+        for ((text, shown), content) in self.texts.iter_mut().zip(renderer.texts()) {
+            if shown != content {
+                text.set_text_content(Some(content));
+                *shown = content.clone();
+            }
+        }
+    }
+}
+
+/// The elements named `tag` in the container, paired with the values they were built with.
+fn shown_elements(
+    container: &web_sys::HtmlElement,
+    tag: &str,
+    values: &[String],
+) -> Vec<(web_sys::Element, String)> {
+    let elements = container.get_elements_by_tag_name(tag);
+    (0..elements.length())
+        .filter_map(|i| elements.item(i))
+        .zip(values.iter().cloned())
+        .collect()
+}
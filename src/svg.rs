@@ -0,0 +1,263 @@
+use crate::board::BOARD_SIZE;
+use crate::renderer::{Rect, Renderer, TextStyle, Transform};
+use crate::utils::Point;
+use std::fmt::Write;
+
+const TEXT_OUTLINE_WIDTH: f64 = 1.0;
+
+/// Renders into an SVG document, with every group of shapes (such as a tile) in a `<g>`
+/// element that is translated to its position.
+///
+/// Alongside the document it writes its layout, which leaves out the group transforms and
+/// the text, so that a document on show only has to be rebuilt when the layout changes and
+/// otherwise just takes the new transforms and text.
+pub struct SvgRenderer {
+    body: String,
+    layout: String,
+    /// The transform of every group, in drawing order
+    transforms: Vec<String>,
+    /// The content of every text element, in drawing order
+    texts: Vec<String>,
+    transform: Option<Transform>,
+    alpha: f64,
+    /// The URL of the picture of a picture puzzle
+    picture: Option<String>,
+}
+
+impl Default for SvgRenderer {
+    fn default() -> SvgRenderer {
+        SvgRenderer::new()
+    }
+}
+
+impl SvgRenderer {
+    pub fn new() -> SvgRenderer {
+        SvgRenderer {
+            body: String::new(),
+            layout: String::new(),
+            transforms: Vec::new(),
+            texts: Vec::new(),
+            transform: None,
+            alpha: 1.0,
+            picture: None,
+        }
+    }
+
+    pub fn set_picture(&mut self, url: Option<String>) {
+        self.picture = url;
+    }
+
+    /// Forgets everything drawn so far, to draw the next frame.
+    pub fn clear(&mut self) {
+        self.body.clear();
+        self.layout.clear();
+        self.transforms.clear();
+        self.texts.clear();
+        self.alpha = 1.0;
+    }
+
+    /// The document drawn so far without its group transforms and text.
+    pub fn layout(&self) -> &str {
+        &self.layout
+    }
+
+    pub fn transforms(&self) -> &[String] {
+        &self.transforms
+    }
+
+    pub fn texts(&self) -> &[String] {
+        &self.texts
+    }
+
+    /// The SVG document of everything drawn so far. Without a transform it fills its
+    /// container, and with one the offset is kept as a margin around the scaled board.
+    pub fn to_svg(&self) -> String {
+        let (view_box, width, height) = match self.transform {
+            Some(Transform { scale, offset }) => {
+                let margin = Point {
+                    x: offset.x / scale.x,
+                    y: offset.y / scale.y,
+                };
+                (
+                    format!(
+                        "{} {} {} {}",
+                        number(-margin.x),
+                        number(-margin.y),
+                        number(BOARD_SIZE + 2.0 * margin.x),
+                        number(BOARD_SIZE + 2.0 * margin.y)
+                    ),
+                    number(BOARD_SIZE * scale.x + 2.0 * offset.x),
+                    number(BOARD_SIZE * scale.y + 2.0 * offset.y),
+                )
+            }
+            None => (
+                format!("0 0 {size} {size}", size = BOARD_SIZE),
+                "100%".to_string(),
+                "100%".to_string(),
+            ),
+        };
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{}" width="{}" height="{}" preserveAspectRatio="xMidYMid meet" role="img" aria-label="Puzzle board">{}</svg>"#,
+            view_box, width, height, self.body
+        )
+    }
+
+    /// Writes an element that is the same in the document and its layout.
+    fn push(&mut self, element: &str) {
+        self.body.push_str(element);
+        self.layout.push_str(element);
+    }
+
+    /// The opacity attribute of the next element, if it is not opaque.
+    fn opacity(&self) -> String {
+        if self.alpha < 1.0 {
+            format!(r#" opacity="{}""#, number(self.alpha))
+        } else {
+            String::new()
+        }
+    }
+}
+
+impl Renderer for SvgRenderer {
+    fn set_transform(&mut self, transform: Transform) {
+        self.transform = Some(transform);
+    }
+
+    fn set_alpha(&mut self, alpha: f64) {
+        self.alpha = alpha;
+    }
+
+    fn fill_rect(&mut self, rect: Rect, colour: &str) {
+        let opacity = self.opacity();
+        let element = format!(
+            r#"<rect {} fill="{}"{}/>"#,
+            rect_attributes(rect),
+            escape(colour),
+            opacity
+        );
+        self.push(&element);
+    }
+
+    fn stroke_rect(&mut self, rect: Rect, colour: &str, line_width: f64) {
+        let opacity = self.opacity();
+        let element = format!(
+            r#"<rect {} fill="none" stroke="{}" stroke-width="{}"{}/>"#,
+            rect_attributes(rect),
+            escape(colour),
+            number(line_width),
+            opacity
+        );
+        self.push(&element);
+    }
+
+    fn text(&mut self, text: &str, centre: Point<f64>, style: &TextStyle) {
+        let outline = if style.outline {
+            format!(
+                r#" stroke="{}" stroke-width="{}""#,
+                escape(style.colour),
+                TEXT_OUTLINE_WIDTH
+            )
+        } else {
+            String::new()
+        };
+        let opacity = self.opacity();
+        let open = format!(
+            r#"<text x="{}" y="{}" font-family="{}" font-size="{}" fill="{}"{}{} text-anchor="middle" dominant-baseline="central">"#,
+            number(centre.x),
+            number(centre.y),
+            escape(style.family),
+            number(style.size),
+            escape(style.colour),
+            outline,
+            opacity
+        );
+        write!(self.body, "{}{}</text>", open, escape(text)).unwrap();
+        write!(self.layout, "{}</text>", open).unwrap();
+        self.texts.push(text.to_string());
+    }
+
+    fn polyline(&mut self, points: &[Point<f64>], colour: &str, line_width: f64) {
+        let points = points
+            .iter()
+            .map(|p| format!("{},{}", number(p.x), number(p.y)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let opacity = self.opacity();
+        let element = format!(
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}"{}/>"#,
+            points,
+            escape(colour),
+            number(line_width),
+            opacity
+        );
+        self.push(&element);
+    }
+
+    fn picture(&mut self, source: Rect, dest: Rect) {
+        let url = match &self.picture {
+            Some(url) => escape(url),
+            None => return,
+        };
+        // A nested viewport showing only the source part of the picture, which is scaled to
+        // cover the unit square and cropped to its centre
+        let opacity = self.opacity();
+        let element = format!(
+            r#"<svg x="{}" y="{}" width="{}" height="{}" viewBox="{} {} {} {}" preserveAspectRatio="none"{}><image href="{}" width="1" height="1" preserveAspectRatio="xMidYMid slice"/></svg>"#,
+            number(dest.x),
+            number(dest.y),
+            number(dest.width),
+            number(dest.height),
+            source.x,
+            source.y,
+            source.width,
+            source.height,
+            opacity,
+            url
+        );
+        self.push(&element);
+    }
+
+    fn begin_group(&mut self, class: &str, offset: Point<f64>) {
+        let transform = format!("translate({} {})", number(offset.x), number(offset.y));
+        write!(
+            self.body,
+            r#"<g class="{}" transform="{}">"#,
+            escape(class),
+            transform
+        )
+        .unwrap();
+        write!(self.layout, r#"<g class="{}">"#, escape(class)).unwrap();
+        self.transforms.push(transform);
+    }
+
+    fn end_group(&mut self) {
+        self.push("</g>");
+    }
+}
+
+fn rect_attributes(rect: Rect) -> String {
+    let mut attributes = format!(
+        r#"x="{}" y="{}" width="{}" height="{}""#,
+        number(rect.x),
+        number(rect.y),
+        number(rect.width),
+        number(rect.height)
+    );
+    if rect.radius > 0.0 {
+        write!(attributes, r#" rx="{}""#, number(rect.radius)).unwrap();
+    }
+    attributes
+}
+
+/// Rounds to hundredths, which is plenty on a board a thousand units wide. Adding zero
+/// turns a negative zero into a plain one.
+fn number(value: f64) -> String {
+    format!("{}", (value * 100.0).round() / 100.0 + 0.0)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,106 @@
+//! Rendering the board as plain text, for the terminal front-end and for logging, and a
+//! plain grid notation for positions.
+//!
+//! In the grid notation every row of slots is a line of whitespace separated slots, with
+//! `.` for a vacant slot, `_` for an empty tile and the number of a numbered tile:
+//!
+//! ```text
+//! . _ _ _ .
+//! _ 1 2 3 .
+//! _ 4 5 6 .
+//! _ 7 8 9 .
+//! . . . . .
+//! ```
+use crate::config::{MAX_SIZE, MIN_SIZE};
+use crate::cursor::Cursor;
+use crate::puzzle::{Cell, PuzzleState};
+use crate::stats::Stats;
+
+/// Draws the move count and timer above the grid, with one line of text per row of slots.
+///
+/// Numbered tiles are drawn as `[ 7]`, empty tiles as `[  ]` and vacant slots are left
+/// blank. The tile under the cursor is drawn as `( 7)`, or as `{ 7}` while grabbed.
+pub fn render(state: &PuzzleState, cursor: Option<&Cursor>, stats: &Stats) -> String {
+    let size = state.size();
+    let label_width = (size as usize * size as usize).to_string().len();
+    let grid_width = (size as usize + 2) * (label_width + 3) - 1;
+
+    let moves = format!("MOVES {}", stats.moves());
+    let time = format!("TIME {}", stats.clock());
+    let gap = grid_width.saturating_sub(moves.len() + time.len()).max(1);
+    let mut lines = vec![format!("{}{}{}", moves, " ".repeat(gap), time)];
+
+    for y in 0..size + 2 {
+        let slots = (0..size + 2)
+            .map(|x| {
+                let (open, close) = match cursor {
+                    Some(c) if c.position.x == x && c.position.y == y && c.grabbed => ('{', '}'),
+                    Some(c) if c.position.x == x && c.position.y == y => ('(', ')'),
+                    _ => ('[', ']'),
+                };
+                match state.get(x, y) {
+                    Cell::Vacant => " ".repeat(label_width + 2),
+                    cell => format!(
+                        "{}{:>width$}{}",
+                        open,
+                        cell.label(),
+                        close,
+                        width = label_width
+                    ),
+                }
+            })
+            .collect::<Vec<_>>();
+        lines.push(slots.join(" ").trim_end().to_string());
+    }
+    lines.join("\n")
+}
+
+/// Writes the position in the grid notation.
+pub fn format_grid(state: &PuzzleState) -> String {
+    let size = state.size();
+    let label_width = (size as usize * size as usize).to_string().len();
+    (0..size + 2)
+        .map(|y| {
+            (0..size + 2)
+                .map(|x| {
+                    let slot = match state.get(x, y) {
+                        Cell::Vacant => ".".to_string(),
+                        Cell::Empty => "_".to_string(),
+                        cell => cell.label(),
+                    };
+                    format!("{:>width$}", slot, width = label_width)
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Reads a position in the grid notation, ignoring blank lines.
+///
+/// Returns `None` unless the grid is square, of a size the game can be played at, and holds
+/// a valid position.
+pub fn parse_grid(text: &str) -> Option<PuzzleState> {
+    let rows: Vec<Vec<&str>> = text
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .filter(|row| !row.is_empty())
+        .collect();
+    let width = rows.len();
+    let playable = (MIN_SIZE as usize + 2)..=(MAX_SIZE as usize + 2);
+    if !playable.contains(&width) || rows.iter().any(|row| row.len() != width) {
+        return None;
+    }
+
+    let cells = rows
+        .iter()
+        .flatten()
+        .map(|slot| match *slot {
+            "." => Some(Cell::Vacant),
+            "_" => Some(Cell::Empty),
+            number => number.parse().ok().map(Cell::Number),
+        })
+        .collect::<Option<Vec<_>>>()?;
+    PuzzleState::from_cells(width as i8 - 2, cells)
+}
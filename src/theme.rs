@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+
+/// How the numbers are written on the tiles.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LabelStyle {
+    Plain,
+    /// Traced with a thin outline in the same colour, which makes them look bolder
+    Outlined,
+    /// Not written at all
+    Hidden,
+}
+
+/// The colours, fonts and shapes used to render the board.
+///
+/// Themes can also be given as JSON with any of the fields, taking the rest from the light theme.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub background: String,
+    pub tile: String,
+    pub empty_tile: String,
+    pub corner: String,
+    pub label: String,
+    /// The colour of the move count and the timer in the corners
+    pub hud: String,
+    /// The colour of the shading over the border slots
+    pub border_shade: String,
+    /// The opacity of the shading over the border slots
+    pub border_alpha: f64,
+    pub cursor: String,
+    pub hint: String,
+    /// The colour of the overlay covering a solved board
+    pub overlay: String,
+    pub overlay_text: String,
+    /// A CSS font family list for all text
+    pub font_family: String,
+    /// The size of the tile labels relative to the tiles
+    pub label_scale: f64,
+    pub label_style: LabelStyle,
+    /// The radius of the tile corners relative to the tiles, from 0 for square tiles to 0.5
+    pub tile_radius: f64,
+}
+
+impl Theme {
+    pub fn light() -> Theme {
+        Theme {
+            background: "rgb(150,200,255)".to_string(),
+            tile: "rgb(40,130,0)".to_string(),
+            empty_tile: "rgb(120,0,20)".to_string(),
+            corner: "rgb(20,20,60)".to_string(),
+            label: "rgb(255,255,255)".to_string(),
+            hud: "rgb(255,255,255)".to_string(),
+            border_shade: "rgb(0,0,0)".to_string(),
+            border_alpha: 0.2,
+            cursor: "rgb(255,255,255)".to_string(),
+            hint: "rgb(255,220,0)".to_string(),
+            overlay: "rgb(255,255,255)".to_string(),
+            overlay_text: "rgb(20,20,60)".to_string(),
+            font_family: "Arial".to_string(),
+            label_scale: 0.64,
+            label_style: LabelStyle::Outlined,
+            tile_radius: 0.0,
+        }
+    }
+
+    pub fn dark() -> Theme {
+        Theme {
+            background: "rgb(30,34,48)".to_string(),
+            tile: "rgb(60,110,170)".to_string(),
+            empty_tile: "rgb(70,70,80)".to_string(),
+            corner: "rgb(10,10,20)".to_string(),
+            label: "rgb(235,235,235)".to_string(),
+            hud: "rgb(235,235,235)".to_string(),
+            border_alpha: 0.35,
+            overlay: "rgb(10,10,20)".to_string(),
+            overlay_text: "rgb(235,235,235)".to_string(),
+            label_style: LabelStyle::Plain,
+            tile_radius: 0.12,
+            ..Theme::light()
+        }
+    }
+
+    /// Black and white with yellow accents, and large labels.
+    pub fn high_contrast() -> Theme {
+        Theme {
+            background: "rgb(0,0,0)".to_string(),
+            tile: "rgb(255,255,255)".to_string(),
+            empty_tile: "rgb(255,255,0)".to_string(),
+            corner: "rgb(0,0,0)".to_string(),
+            label: "rgb(0,0,0)".to_string(),
+            hud: "rgb(255,255,0)".to_string(),
+            border_shade: "rgb(0,0,0)".to_string(),
+            border_alpha: 0.25,
+            cursor: "rgb(255,0,255)".to_string(),
+            hint: "rgb(0,255,255)".to_string(),
+            overlay: "rgb(0,0,0)".to_string(),
+            overlay_text: "rgb(255,255,0)".to_string(),
+            font_family: "Verdana, sans-serif".to_string(),
+            label_scale: 0.72,
+            label_style: LabelStyle::Outlined,
+            tile_radius: 0.0,
+        }
+    }
+
+    pub fn by_name(name: &str) -> Option<Theme> {
+        match name {
+            "light" => Some(Theme::light()),
+            "dark" => Some(Theme::dark()),
+            "high-contrast" => Some(Theme::high_contrast()),
+            _ => None,
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Theme, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::light()
+    }
+}
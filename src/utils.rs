@@ -1,4 +1,6 @@
 use num::Num;
+use serde::{Deserialize, Serialize};
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
 
 pub fn set_panic_hook() {
@@ -12,6 +14,7 @@ pub fn set_panic_hook() {
     console_error_panic_hook::set_once();
 }
 
+#[cfg(target_arch = "wasm32")]
 #[wasm_bindgen]
 extern "C" {
     // Use `js_namespace` here to bind `console.log(..)` instead of just
@@ -20,11 +23,17 @@ extern "C" {
     pub fn log(s: &str);
 }
 
+/// Outside the browser, such as in the command-line tools, logs to standard error.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn log(s: &str) {
+    eprintln!("{}", s);
+}
+
 pub fn window() -> web_sys::Window {
     web_sys::window().expect("no global `window` exists")
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Point<T: Copy + Num> {
     pub x: T,
     pub y: T,
@@ -83,6 +92,7 @@ impl<T: Copy + Num> std::ops::Div<T> for Point<T> {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Direction {
     North,
     South,
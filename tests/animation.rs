@@ -0,0 +1,51 @@
+use puzzle::animation::{Easing, ShiftAction, Tween};
+use puzzle::puzzle::{Direction, Shift};
+
+const EASINGS: [Easing; 3] = [Easing::Linear, Easing::EaseOut, Easing::Spring];
+
+#[test]
+fn easings_start_at_zero_and_end_at_one() {
+    for easing in &EASINGS {
+        assert!(easing.apply(0.0).abs() < 1e-9, "{:?}", easing);
+        assert_eq!(easing.apply(1.0), 1.0, "{:?}", easing);
+        assert_eq!(easing.apply(1.5), 1.0, "{:?}", easing);
+    }
+    assert!(Easing::EaseOut.apply(0.5) > Easing::Linear.apply(0.5));
+}
+
+#[test]
+fn springs_overshoot_before_settling() {
+    let peak = (1..100)
+        .map(|i| Easing::Spring.apply(i as f64 / 100.0))
+        .fold(0.0, f64::max);
+    assert!(peak > 1.0 && peak < 1.2, "{}", peak);
+}
+
+#[test]
+fn tweens_progress_with_time() {
+    let mut tween = Tween::new(2.0, 4.0, 200.0, Easing::Linear);
+    assert_eq!(tween.advance(50.0), 2.5);
+    assert!(!tween.is_finished());
+    assert_eq!(tween.advance(150.0), 4.0);
+    assert!(tween.is_finished());
+
+    let instant = Tween::new(0.0, 1.0, 0.0, Easing::Spring);
+    assert!(instant.is_finished());
+    assert_eq!(instant.value(), 1.0);
+}
+
+#[test]
+fn released_shifts_move_to_where_they_were_dropped() {
+    let shift = Shift {
+        idx: 2,
+        direction: Direction::West,
+    };
+    let mut committed = ShiftAction::release(&shift, 0.7, true, 100.0, Easing::EaseOut);
+    assert_eq!(committed.progress.value(), 0.7);
+    assert_eq!(committed.progress.advance(100.0), 1.0);
+    assert_eq!(committed.shift(), shift);
+
+    let mut cancelled = ShiftAction::release(&shift, 0.3, false, 100.0, Easing::EaseOut);
+    assert!(!cancelled.commit);
+    assert_eq!(cancelled.progress.advance(100.0), 0.0);
+}
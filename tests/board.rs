@@ -0,0 +1,167 @@
+use puzzle::board::{Board, BOARD_SIZE};
+use puzzle::puzzle::{Direction, Point, Shift};
+use puzzle::renderer::{DrawCommand, RecordingRenderer, Rect};
+use puzzle::stats::Stats;
+use puzzle::theme::Theme;
+
+fn render(board: &Board) -> RecordingRenderer {
+    let mut renderer = RecordingRenderer::default();
+    board.render(&mut renderer, &Stats::default());
+    renderer
+}
+
+#[test]
+fn renders_every_tile_with_its_label_and_the_hud() {
+    let board = Board::new(3, Theme::light());
+    let renderer = render(&board);
+
+    assert_eq!(
+        renderer.commands[0],
+        DrawCommand::FillRect(
+            Rect::new(0.0, 0.0, BOARD_SIZE, BOARD_SIZE),
+            Theme::light().background
+        )
+    );
+    assert_eq!(
+        renderer.texts(),
+        vec!["1", "2", "3", "4", "5", "6", "7", "8", "9", "MOVES", "0", "TIME", "0:00"]
+    );
+    let tile_outlines = renderer
+        .commands
+        .iter()
+        .filter(|command| matches!(command, DrawCommand::StrokeRect(..)))
+        .count();
+    assert_eq!(tile_outlines, 9 + 6);
+}
+
+#[test]
+fn displaced_rows_are_rendered_off_the_grid() {
+    let mut board = Board::new(3, Theme::light());
+    let tile_size = board.tile_size();
+    let from = Point {
+        x: 1.5 * tile_size,
+        y: 2.5 * tile_size,
+    };
+    let to = Point {
+        x: from.x + 0.3 * tile_size,
+        y: from.y,
+    };
+    board.drag(&from, &to);
+
+    // Tiles are drawn in groups positioned where the tile is shown
+    let renderer = render(&board);
+    let mut group = None;
+    let mut tile_4 = None;
+    for command in &renderer.commands {
+        match command {
+            DrawCommand::BeginGroup(_, offset) => group = Some(*offset),
+            DrawCommand::Text { text, .. } if text == "4" => tile_4 = group,
+            _ => (),
+        }
+    }
+    let tile_4 = tile_4.unwrap();
+    assert!((tile_4.x - 1.3 * tile_size).abs() < 1e-9);
+    assert!((tile_4.y - 2.0 * tile_size).abs() < 1e-9);
+}
+
+#[test]
+fn hints_are_drawn_as_arrows_and_cleared_by_shifting() {
+    let mut board = Board::new(3, Theme::light());
+    board.set_hint(Some(Shift {
+        idx: 1,
+        direction: Direction::East,
+    }));
+    let arrows = |renderer: &RecordingRenderer| {
+        renderer
+            .commands
+            .iter()
+            .filter(|command| matches!(command, DrawCommand::Polyline(..)))
+            .count()
+    };
+    assert_eq!(arrows(&render(&board)), 2);
+
+    let shift = board.state().row_shift(1).unwrap();
+    assert!(board.apply_shift(&shift));
+    assert_eq!(arrows(&render(&board)), 0);
+}
+
+#[test]
+fn solved_overlay_covers_the_board() {
+    let board = Board::new(3, Theme::dark());
+    let mut renderer = RecordingRenderer::default();
+    board.render_solved_overlay(&mut renderer);
+
+    assert!(matches!(
+        renderer.commands.as_slice(),
+        [
+            DrawCommand::Alpha(_),
+            DrawCommand::FillRect(..),
+            DrawCommand::Alpha(_),
+            DrawCommand::Text { .. }
+        ]
+    ));
+    assert_eq!(renderer.texts(), vec!["Solved!"]);
+}
+
+#[test]
+fn picture_tiles_show_the_part_where_they_belong() {
+    let mut board = Board::new(3, Theme::light());
+    board.set_picture(true);
+    board.set_state({
+        let mut state = board.state().clone();
+        state.apply(&Shift {
+            idx: 1,
+            direction: Direction::East,
+        });
+        state
+    });
+    let renderer = render(&board);
+
+    let pictures: Vec<(Point<f64>, Rect)> = {
+        let mut group = None;
+        renderer
+            .commands
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::BeginGroup(_, offset) => {
+                    group = Some(*offset);
+                    None
+                }
+                DrawCommand::Picture(source, _) => Some((group.unwrap(), *source)),
+                _ => None,
+            })
+            .collect()
+    };
+    assert_eq!(pictures.len(), 9);
+    // Tile 3 has been shifted onto the border, but still shows the top right of the picture
+    let third = 1.0 / 3.0;
+    assert!(pictures.contains(&(
+        Point { x: 800.0, y: 200.0 },
+        Rect::new(2.0 * third, 0.0, third, third)
+    )));
+
+    // Labels are faint over the picture
+    let label = renderer
+        .commands
+        .iter()
+        .position(|command| matches!(command, DrawCommand::Text { text, .. } if text == "5"))
+        .unwrap();
+    assert!(matches!(renderer.commands[label - 1], DrawCommand::Alpha(alpha) if alpha < 1.0));
+    assert_eq!(renderer.commands[label + 1], DrawCommand::Alpha(1.0));
+}
+
+#[test]
+fn displacements_tell_the_shift_they_lead_to() {
+    let mut board = Board::new(3, Theme::light());
+    let tile_size = board.tile_size();
+    let shift = board.state().col_shift(1).unwrap();
+    board.displace(&shift, 0.25 * tile_size);
+    let (displaced, progress) = board.displacement().unwrap();
+    assert_eq!(displaced, shift);
+    assert!((progress - 0.25).abs() < 1e-9);
+
+    board.displace(&shift, 0.0);
+    assert_eq!(board.displacement(), None);
+    assert!(board.apply_shift(&shift));
+    assert!(!board.apply_shift(&shift));
+}
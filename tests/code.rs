@@ -0,0 +1,49 @@
+use puzzle::code::{decode, encode};
+use puzzle::config::{MAX_SIZE, MIN_SIZE};
+use puzzle::puzzle::PuzzleState;
+use puzzle::rng::SeededRng;
+use puzzle::shuffle::shuffle;
+
+#[test]
+fn codes_round_trip_for_every_size() {
+    for size in 2..=9 {
+        let mut state = PuzzleState::new(size);
+        for shift in shuffle(size, 30, &mut SeededRng::new(size as u64)) {
+            state.apply(&shift);
+        }
+
+        let code = encode(&state);
+        assert!(code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(decode(&code), Some(state));
+    }
+}
+
+#[test]
+fn codes_are_short() {
+    assert_eq!(encode(&PuzzleState::new(3)).len(), 12);
+    assert!(encode(&PuzzleState::new(4)).len() <= 24);
+}
+
+#[test]
+fn invalid_codes_are_rejected() {
+    let code = encode(&PuzzleState::new(3));
+
+    assert_eq!(decode(""), None);
+    assert_eq!(decode("A"), None);
+    assert_eq!(decode(&code[..code.len() - 1]), None);
+    assert_eq!(decode(&format!("{}A", code)), None);
+    assert_eq!(decode(&code.replace('D', "!")), None);
+    // Tile 1 replaced by a third empty tile in the first row
+    assert_eq!(code, "DAAAASMEVgeJ");
+    assert_eq!(decode("DAAAACMEVgeJ"), None);
+}
+
+#[test]
+fn codes_of_unplayable_sizes_are_rejected() {
+    for size in [1, MAX_SIZE + 1].iter() {
+        assert_eq!(decode(&encode(&PuzzleState::new(*size))), None);
+    }
+    assert!(decode(&encode(&PuzzleState::new(MIN_SIZE))).is_some());
+}
@@ -0,0 +1,36 @@
+use puzzle::config::GameConfig;
+use puzzle::shuffle::Difficulty;
+
+#[test]
+fn save_keys_separate_size_scramble_and_seed() {
+    let mut config = GameConfig::new();
+    let key = config.save_key(3).unwrap();
+    assert_ne!(config.save_key(4).unwrap(), key);
+
+    config.set_shuffle_steps(20);
+    let steps = config.save_key(3).unwrap();
+    assert_ne!(steps, key);
+
+    config.set_difficulty(Some(Difficulty::Hard));
+    let hard = config.save_key(3).unwrap();
+    assert_ne!(hard, steps);
+    config.set_difficulty(Some(Difficulty::Easy));
+    assert_ne!(config.save_key(3).unwrap(), hard);
+
+    config.set_seed(Some(7));
+    let seeded = config.save_key(3).unwrap();
+    config.set_seed(Some(8));
+    assert_ne!(config.save_key(3).unwrap(), seeded);
+
+    config.set_persist(false);
+    assert_eq!(config.save_key(3), None);
+}
+
+#[test]
+fn games_are_shuffled_at_least_once() {
+    let mut config = GameConfig::new();
+    config.set_shuffle_steps(0);
+    let once = config.save_key(3);
+    config.set_shuffle_steps(1);
+    assert_eq!(config.save_key(3), once);
+}
@@ -0,0 +1,52 @@
+use puzzle::cursor::{arrow_direction, Cursor};
+use puzzle::puzzle::{Direction, Point, Shift};
+
+#[test]
+fn moving_stays_on_the_numbered_slots() {
+    let mut cursor = Cursor::new();
+    cursor.move_towards(&Direction::North, 3);
+    cursor.move_towards(&Direction::West, 3);
+    assert_eq!(cursor.position, Point { x: 1, y: 1 });
+
+    for _ in 0..5 {
+        cursor.move_towards(&Direction::East, 3);
+        cursor.move_towards(&Direction::South, 3);
+    }
+    assert_eq!(cursor.position, Point { x: 3, y: 3 });
+
+    cursor.move_towards(&Direction::West, 3);
+    assert_eq!(cursor.position, Point { x: 2, y: 3 });
+}
+
+#[test]
+fn shifts_the_selected_row_or_column() {
+    let cursor = Cursor {
+        position: Point { x: 2, y: 3 },
+        grabbed: false,
+    };
+    for direction in [Direction::West, Direction::East].iter() {
+        assert_eq!(
+            cursor.shift_towards(*direction),
+            Shift {
+                idx: 3,
+                direction: *direction
+            }
+        );
+    }
+    for direction in [Direction::North, Direction::South].iter() {
+        assert_eq!(
+            cursor.shift_towards(*direction),
+            Shift {
+                idx: 2,
+                direction: *direction
+            }
+        );
+    }
+}
+
+#[test]
+fn arrow_keys_map_to_directions() {
+    assert_eq!(arrow_direction("ArrowUp"), Some(Direction::North));
+    assert_eq!(arrow_direction("ArrowRight"), Some(Direction::East));
+    assert_eq!(arrow_direction("a"), None);
+}
@@ -0,0 +1,106 @@
+use puzzle::history::History;
+use puzzle::puzzle::{Direction, Shift};
+
+#[test]
+fn undo_returns_the_inverse_shift() {
+    let mut history = History::default();
+    history.record(Shift {
+        idx: 1,
+        direction: Direction::East,
+    });
+    history.record(Shift {
+        idx: 2,
+        direction: Direction::South,
+    });
+
+    assert_eq!(
+        history.undo(),
+        Some(Shift {
+            idx: 2,
+            direction: Direction::North
+        })
+    );
+    assert_eq!(
+        history.done(),
+        &[Shift {
+            idx: 1,
+            direction: Direction::East
+        }]
+    );
+    assert_eq!(
+        history.undo(),
+        Some(Shift {
+            idx: 1,
+            direction: Direction::West
+        })
+    );
+    assert_eq!(history.undo(), None);
+}
+
+#[test]
+fn redo_replays_the_undone_shift() {
+    let mut history = History::default();
+    history.record(Shift {
+        idx: 1,
+        direction: Direction::East,
+    });
+    history.record(Shift {
+        idx: 2,
+        direction: Direction::South,
+    });
+    assert_eq!(history.redo(), None);
+
+    history.undo();
+    history.undo();
+    assert_eq!(
+        history.redo(),
+        Some(Shift {
+            idx: 1,
+            direction: Direction::East
+        })
+    );
+    assert_eq!(
+        history.redo(),
+        Some(Shift {
+            idx: 2,
+            direction: Direction::South
+        })
+    );
+    assert_eq!(history.redo(), None);
+    assert_eq!(
+        history.done(),
+        &[
+            Shift {
+                idx: 1,
+                direction: Direction::East
+            },
+            Shift {
+                idx: 2,
+                direction: Direction::South
+            }
+        ]
+    );
+}
+
+#[test]
+fn recording_clears_the_redo_stack() {
+    let mut history = History::default();
+    history.record(Shift {
+        idx: 1,
+        direction: Direction::East,
+    });
+    history.undo();
+
+    history.record(Shift {
+        idx: 3,
+        direction: Direction::North,
+    });
+    assert_eq!(history.redo(), None);
+    assert_eq!(
+        history.done(),
+        &[Shift {
+            idx: 3,
+            direction: Direction::North
+        }]
+    );
+}
@@ -0,0 +1,114 @@
+use puzzle::notation::{format_moves, parse_moves, ParseMoveError};
+use puzzle::puzzle::{Direction, Shift};
+
+#[test]
+fn moves_are_formatted_with_line_index_and_arrow() {
+    assert_eq!(
+        Shift {
+            idx: 2,
+            direction: Direction::East
+        }
+        .to_string(),
+        "R2>"
+    );
+    assert_eq!(
+        Shift {
+            idx: 3,
+            direction: Direction::West
+        }
+        .to_string(),
+        "R3<"
+    );
+    assert_eq!(
+        Shift {
+            idx: 1,
+            direction: Direction::North
+        }
+        .to_string(),
+        "C1^"
+    );
+    assert_eq!(
+        Shift {
+            idx: 4,
+            direction: Direction::South
+        }
+        .to_string(),
+        "C4v"
+    );
+    assert_eq!(
+        format_moves(&[
+            Shift {
+                idx: 2,
+                direction: Direction::East
+            },
+            Shift {
+                idx: 1,
+                direction: Direction::North
+            }
+        ]),
+        "R2> C1^"
+    );
+    assert_eq!(format_moves(&[]), "");
+}
+
+#[test]
+fn formatted_moves_parse_back() {
+    let shifts = vec![
+        Shift {
+            idx: 1,
+            direction: Direction::East,
+        },
+        Shift {
+            idx: 12,
+            direction: Direction::West,
+        },
+        Shift {
+            idx: 3,
+            direction: Direction::North,
+        },
+        Shift {
+            idx: 9,
+            direction: Direction::South,
+        },
+    ];
+    assert_eq!(parse_moves(&format_moves(&shifts)), Ok(shifts));
+    assert_eq!(
+        parse_moves("  r2>\n\tc1V "),
+        Ok(vec![
+            Shift {
+                idx: 2,
+                direction: Direction::East
+            },
+            Shift {
+                idx: 1,
+                direction: Direction::South
+            }
+        ])
+    );
+    assert_eq!(parse_moves(""), Ok(vec![]));
+}
+
+#[test]
+fn invalid_moves_are_rejected() {
+    assert_eq!(
+        "X2>".parse::<Shift>(),
+        Err(ParseMoveError::Line("X2>".to_string()))
+    );
+    assert_eq!(
+        "R0>".parse::<Shift>(),
+        Err(ParseMoveError::Index("R0>".to_string()))
+    );
+    assert_eq!(
+        "R+2>".parse::<Shift>(),
+        Err(ParseMoveError::Index("R+2>".to_string()))
+    );
+    assert_eq!(
+        "R2".parse::<Shift>(),
+        Err(ParseMoveError::Direction("R2".to_string()))
+    );
+    assert_eq!(
+        "C2>".parse::<Shift>(),
+        Err(ParseMoveError::Direction("C2>".to_string()))
+    );
+    assert!(parse_moves("R1> C2< R3>").is_err());
+}
@@ -0,0 +1,184 @@
+use puzzle::puzzle::{Cell, Direction, PuzzleState, Shift};
+
+#[test]
+fn new_state_matches_initial_board() {
+    let state = PuzzleState::new(3);
+
+    assert_eq!(state.get(1, 1), Cell::Number(1));
+    assert_eq!(state.get(3, 1), Cell::Number(3));
+    assert_eq!(state.get(1, 3), Cell::Number(7));
+    assert_eq!(state.get(2, 0), Cell::Empty);
+    assert_eq!(state.get(0, 2), Cell::Empty);
+    assert_eq!(state.get(4, 2), Cell::Vacant);
+    assert_eq!(state.get(0, 0), Cell::Vacant);
+    assert_eq!(state.tiles().count(), 9 + 6);
+}
+
+#[test]
+fn large_boards_are_numbered_without_overflowing() {
+    let state = PuzzleState::new(15);
+    assert_eq!(state.get(15, 15), Cell::Number(225));
+    assert!(state.is_solved());
+}
+
+#[test]
+#[should_panic]
+fn boards_too_large_to_number_are_rejected() {
+    PuzzleState::new(16);
+}
+
+#[test]
+fn every_line_has_exactly_one_legal_shift() {
+    let mut state = PuzzleState::new(3);
+    assert_eq!(
+        state.legal_shifts(),
+        vec![
+            Shift {
+                idx: 1,
+                direction: Direction::East
+            },
+            Shift {
+                idx: 2,
+                direction: Direction::East
+            },
+            Shift {
+                idx: 3,
+                direction: Direction::East
+            },
+            Shift {
+                idx: 1,
+                direction: Direction::South
+            },
+            Shift {
+                idx: 2,
+                direction: Direction::South
+            },
+            Shift {
+                idx: 3,
+                direction: Direction::South
+            },
+        ]
+    );
+
+    assert!(state.apply(&Shift {
+        idx: 2,
+        direction: Direction::East
+    }));
+    assert_eq!(
+        state.row_shift(2),
+        Some(Shift {
+            idx: 2,
+            direction: Direction::West
+        })
+    );
+    assert_eq!(state.row_shift(0), None);
+    assert_eq!(state.col_shift(4), None);
+}
+
+#[test]
+fn illegal_shifts_are_rejected() {
+    let mut state = PuzzleState::new(3);
+    let initial = state.clone();
+
+    assert!(!state.apply(&Shift {
+        idx: 1,
+        direction: Direction::West
+    }));
+    assert!(!state.apply(&Shift {
+        idx: 2,
+        direction: Direction::North
+    }));
+    assert!(!state.apply(&Shift {
+        idx: 4,
+        direction: Direction::East
+    }));
+    assert_eq!(state, initial);
+}
+
+#[test]
+fn shifts_move_whole_lines_and_can_be_reverted() {
+    let mut state = PuzzleState::new(3);
+    let initial = state.clone();
+
+    state.apply(&Shift {
+        idx: 1,
+        direction: Direction::East,
+    });
+    assert_eq!(state.get(0, 1), Cell::Vacant);
+    assert_eq!(state.get(1, 1), Cell::Empty);
+    assert_eq!(state.get(4, 1), Cell::Number(3));
+
+    state.apply(&Shift {
+        idx: 2,
+        direction: Direction::South,
+    });
+    assert_eq!(state.get(2, 0), Cell::Vacant);
+    assert_eq!(state.get(2, 1), Cell::Empty);
+    assert_eq!(state.get(2, 2), Cell::Number(1));
+    assert_eq!(state.get(2, 4), Cell::Number(8));
+
+    state.apply(
+        &Shift {
+            idx: 2,
+            direction: Direction::South,
+        }
+        .inverse(),
+    );
+    state.apply(
+        &Shift {
+            idx: 1,
+            direction: Direction::East,
+        }
+        .inverse(),
+    );
+    assert_eq!(state, initial);
+}
+
+#[test]
+fn solved_only_when_numbers_are_home() {
+    let mut state = PuzzleState::new(3);
+    assert!(state.is_solved());
+
+    state.apply(&Shift {
+        idx: 3,
+        direction: Direction::East,
+    });
+    assert!(!state.is_solved());
+
+    state.apply(&Shift {
+        idx: 3,
+        direction: Direction::West,
+    });
+    assert!(state.is_solved());
+}
+
+#[test]
+fn from_cells_accepts_only_valid_layouts() {
+    let mut state = PuzzleState::new(3);
+    state.apply(&Shift {
+        idx: 2,
+        direction: Direction::East,
+    });
+    state.apply(&Shift {
+        idx: 1,
+        direction: Direction::South,
+    });
+    assert_eq!(
+        PuzzleState::from_cells(3, state.cells().to_vec()),
+        Some(state.clone())
+    );
+
+    let mut duplicate = state.cells().to_vec();
+    duplicate[6] = Cell::Number(2);
+    assert_eq!(PuzzleState::from_cells(3, duplicate), None);
+
+    let mut corner = state.cells().to_vec();
+    corner[0] = Cell::Empty;
+    assert_eq!(PuzzleState::from_cells(3, corner), None);
+
+    assert_eq!(
+        PuzzleState::from_cells(3, state.cells()[1..].to_vec()),
+        None
+    );
+    assert_eq!(PuzzleState::from_cells(4, state.cells().to_vec()), None);
+}
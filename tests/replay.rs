@@ -0,0 +1,130 @@
+use puzzle::puzzle::{Direction, PuzzleState, Shift};
+use puzzle::replay::{Playback, Replay};
+
+fn recorded() -> Replay {
+    let mut replay = Replay::new(&PuzzleState::new(3));
+    replay.record(
+        500.0,
+        Shift {
+            idx: 2,
+            direction: Direction::East,
+        },
+        true,
+    );
+    replay.record(
+        1200.0,
+        Shift {
+            idx: 1,
+            direction: Direction::South,
+        },
+        true,
+    );
+    // Undoing the first move
+    replay.record(
+        1250.0,
+        Shift {
+            idx: 2,
+            direction: Direction::West,
+        },
+        false,
+    );
+    replay
+}
+
+#[test]
+fn replays_round_trip_through_json() {
+    let replay = recorded();
+    let json = replay.to_json();
+    assert!(json.contains(r#"{"time":500.0,"move":"R2>"}"#));
+    assert!(json.contains(r#"{"time":1250.0,"move":"R2<","counted":false}"#));
+    assert_eq!(Replay::from_json(&json).unwrap(), replay);
+    assert!(
+        Replay::from_json(r#"{"start":"DAAAASMEVgeJ","moves":[{"time":1,"move":"R2^"}]}"#).is_err()
+    );
+}
+
+#[test]
+fn playback_plays_due_moves_at_its_speed() {
+    let mut playback = Playback::new(recorded()).unwrap();
+    assert_eq!(playback.advance(400.0), vec![]);
+    assert_eq!(
+        playback.advance(100.0),
+        vec![Shift {
+            idx: 2,
+            direction: Direction::East
+        }]
+    );
+
+    playback.set_paused(true);
+    assert_eq!(playback.advance(1000.0), vec![]);
+    assert_eq!(playback.time(), 500.0);
+
+    playback.set_paused(false);
+    playback.set_speed(4.0);
+    assert_eq!(
+        playback.advance(200.0),
+        vec![
+            Shift {
+                idx: 1,
+                direction: Direction::South
+            },
+            Shift {
+                idx: 2,
+                direction: Direction::West
+            }
+        ]
+    );
+    assert!(playback.is_finished());
+    assert_eq!(playback.time(), playback.duration());
+}
+
+#[test]
+fn seeking_replays_the_moves_up_to_then() {
+    let mut playback = Playback::new(recorded()).unwrap();
+
+    let mut expected = PuzzleState::new(3);
+    expected.apply(&Shift {
+        idx: 2,
+        direction: Direction::East,
+    });
+    expected.apply(&Shift {
+        idx: 1,
+        direction: Direction::South,
+    });
+    assert_eq!(playback.seek(1200.0), expected);
+    assert_eq!(playback.played(), 2);
+    assert_eq!(
+        playback.advance(50.0),
+        vec![Shift {
+            idx: 2,
+            direction: Direction::West
+        }]
+    );
+    assert_eq!(playback.played(), 3);
+    assert_eq!(playback.counted(), 2);
+
+    assert_eq!(playback.seek(-10.0), PuzzleState::new(3));
+    assert_eq!(playback.played(), 0);
+}
+
+#[test]
+fn replays_with_illegal_or_unordered_moves_are_rejected() {
+    let mut illegal = Replay::new(&PuzzleState::new(3));
+    illegal.record(
+        100.0,
+        Shift {
+            idx: 2,
+            direction: Direction::West,
+        },
+        true,
+    );
+    assert!(Playback::new(illegal).is_none());
+
+    let mut unordered = recorded();
+    unordered.moves.swap(0, 1);
+    assert!(Playback::new(unordered).is_none());
+
+    let mut invalid_start = recorded();
+    invalid_start.start = "D".to_string();
+    assert!(Playback::new(invalid_start).is_none());
+}
@@ -0,0 +1,92 @@
+use puzzle::puzzle::PuzzleState;
+use puzzle::rng::{Rng, SeededRng};
+use puzzle::shuffle::{scramble, shuffle, Difficulty};
+use puzzle::solver::solve;
+
+fn shuffled_state(seed: u64) -> PuzzleState {
+    let mut state = PuzzleState::new(3);
+    for shift in shuffle(3, 10, &mut SeededRng::new(seed)) {
+        assert!(state.apply(&shift), "{:?} is not legal", shift);
+    }
+    state
+}
+
+#[test]
+fn seeded_rng_is_deterministic() {
+    let mut a = SeededRng::new(42);
+    let mut b = SeededRng::new(42);
+    let mut c = SeededRng::new(43);
+
+    let sequence: Vec<u64> = (0..8).map(|_| a.next_u64()).collect();
+    assert_eq!(sequence, (0..8).map(|_| b.next_u64()).collect::<Vec<_>>());
+    assert_ne!(sequence, (0..8).map(|_| c.next_u64()).collect::<Vec<_>>());
+}
+
+#[test]
+fn random_numbers_are_in_unit_range() {
+    let mut rng = SeededRng::new(7);
+    assert!((0..1000)
+        .map(|_| rng.random())
+        .all(|r| (0.0..1.0).contains(&r)));
+}
+
+#[test]
+fn same_seed_gives_same_scramble() {
+    assert_eq!(shuffled_state(1234), shuffled_state(1234));
+    assert_ne!(shuffled_state(1234), shuffled_state(4321));
+}
+
+#[test]
+fn scrambles_match_their_difficulty() {
+    for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard].iter() {
+        for seed in 0..3 {
+            let mut state = PuzzleState::new(3);
+            for shift in scramble(3, *difficulty, &mut SeededRng::new(seed)) {
+                assert!(state.apply(&shift));
+            }
+
+            let solution = solve(&state, 20).unwrap();
+            assert!(difficulty
+                .solution_lengths()
+                .contains(&solution.shifts.len()));
+        }
+    }
+}
+
+#[test]
+fn difficulties_parse_from_their_names() {
+    assert_eq!("easy".parse(), Ok(Difficulty::Easy));
+    assert_eq!("hard".parse(), Ok(Difficulty::Hard));
+    assert!("Medium".parse::<Difficulty>().is_err());
+    assert_eq!(
+        Difficulty::Medium.to_string().parse(),
+        Ok(Difficulty::Medium)
+    );
+}
+
+#[test]
+fn hard_scrambles_of_the_smallest_board_are_unsolved_legal_walks() {
+    for seed in 0..20 {
+        let mut state = PuzzleState::new(2);
+        let shifts = scramble(2, Difficulty::Hard, &mut SeededRng::new(seed));
+        assert!(!shifts.is_empty());
+        for shift in &shifts {
+            assert!(state.apply(shift));
+        }
+        assert!(!state.is_solved(), "seed {} is solved", seed);
+    }
+}
+
+#[test]
+fn shuffles_never_leave_the_board_solved() {
+    // A single shift is always undone by arranging the board, and 65 cancels out on 3x3
+    for (size, steps) in [(2, 1), (2, 10), (3, 0), (3, 1), (3, 10), (4, 10)].iter() {
+        for seed in 0..200 {
+            let mut state = PuzzleState::new(*size);
+            for shift in shuffle(*size, *steps, &mut SeededRng::new(seed)) {
+                assert!(state.apply(&shift));
+            }
+            assert!(!state.is_solved(), "seed {} solves {}x{}", seed, size, size);
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use puzzle::puzzle::{Direction, PuzzleState, Shift};
+use puzzle::rng::SeededRng;
+use puzzle::shuffle::shuffle;
+use puzzle::solver::{
+    heuristic, solve, solve_bfs, solve_for_game, solve_greedy, solve_ida, solve_within,
+    SearchFailure, MAX_AUTO_SOLVE_LENGTH, MAX_DEPTH, SEARCH_BUDGET,
+};
+use std::time::{Duration, Instant};
+
+const SCRAMBLES: [&[(u8, Direction)]; 4] = [
+    &[(2, Direction::East)],
+    &[
+        (1, Direction::East),
+        (2, Direction::South),
+        (1, Direction::West),
+    ],
+    &[
+        (3, Direction::South),
+        (2, Direction::East),
+        (3, Direction::North),
+        (1, Direction::South),
+        (2, Direction::West),
+    ],
+    &[
+        (1, Direction::East),
+        (1, Direction::South),
+        (2, Direction::East),
+        (3, Direction::South),
+        (1, Direction::West),
+        (2, Direction::South),
+        (3, Direction::East),
+    ],
+];
+
+fn scrambled(size: i8, shifts: &[(u8, Direction)]) -> PuzzleState {
+    let mut state = PuzzleState::new(size);
+    for (idx, direction) in shifts {
+        assert!(state.apply(&Shift {
+            idx: *idx,
+            direction: *direction,
+        }));
+    }
+    state
+}
+
+fn assert_solves(state: &PuzzleState, shifts: &[Shift]) {
+    let mut state = state.clone();
+    for shift in shifts {
+        assert!(state.apply(shift), "{:?} is not legal", shift);
+    }
+    assert!(state.is_solved());
+}
+
+#[test]
+fn solved_state_needs_no_shifts() {
+    let solution = solve(&PuzzleState::new(3), 10).unwrap();
+    assert!(solution.shifts.is_empty());
+}
+
+#[test]
+fn single_shift_is_undone() {
+    let state = scrambled(3, SCRAMBLES[0]);
+    let solution = solve(&state, 10).unwrap();
+    assert_eq!(
+        solution.shifts,
+        vec![Shift {
+            idx: 2,
+            direction: Direction::West
+        }]
+    );
+}
+
+#[test]
+fn ida_finds_shortest_solutions() {
+    for shifts in SCRAMBLES.iter() {
+        let state = scrambled(3, shifts);
+        let bfs = solve_bfs(&state, 10).unwrap();
+        let ida = solve_ida(&state, 10).unwrap();
+
+        assert_solves(&state, &ida.shifts);
+        assert_eq!(ida.shifts.len(), bfs.shifts.len());
+        assert!(heuristic(&state) <= ida.shifts.len());
+    }
+}
+
+#[test]
+fn larger_boards_are_solved() {
+    for shifts in SCRAMBLES.iter() {
+        let state = scrambled(4, shifts);
+        let solution = solve(&state, 10).unwrap();
+        assert_solves(&state, &solution.shifts);
+        assert!(solution.shifts.len() <= shifts.len());
+    }
+}
+
+#[test]
+fn search_gives_up_beyond_max_depth() {
+    let state = scrambled(3, SCRAMBLES[3]);
+    assert!(solve(&state, 2).is_none());
+    assert!(matches!(
+        solve_within(&state, 2, SEARCH_BUDGET),
+        Err(SearchFailure::Exhausted { .. })
+    ));
+}
+
+#[test]
+fn budgeted_searches_give_up() {
+    let mut state = PuzzleState::new(4);
+    for shift in shuffle(4, 30, &mut SeededRng::new(5)) {
+        state.apply(&shift);
+    }
+    let solution = solve_within(&state, MAX_DEPTH, 1000);
+    assert_eq!(
+        solution.unwrap_err(),
+        SearchFailure::OutOfBudget { expanded: 1000 }
+    );
+
+    let easy = scrambled(3, SCRAMBLES[3]);
+    let solution = solve_within(&easy, MAX_DEPTH, SEARCH_BUDGET).unwrap();
+    assert!(solution.expanded <= SEARCH_BUDGET);
+}
+
+#[test]
+fn greedy_searches_solve_boards_beyond_the_budget() {
+    let mut state = PuzzleState::new(4);
+    for shift in shuffle(4, 10, &mut SeededRng::new(1)) {
+        state.apply(&shift);
+    }
+    assert!(solve_within(&state, MAX_DEPTH, SEARCH_BUDGET).is_err());
+
+    let solution = solve_greedy(&state, SEARCH_BUDGET).unwrap();
+    assert!(solution.expanded <= SEARCH_BUDGET);
+    for shift in &solution.shifts {
+        assert!(state.apply(shift));
+    }
+    assert!(state.is_solved());
+}
+
+#[test]
+fn greedy_effort_is_bounded_on_large_boards() {
+    let mut near = PuzzleState::new(9);
+    for shift in shuffle(9, 5, &mut SeededRng::new(1)) {
+        near.apply(&shift);
+    }
+    let solution = solve_greedy(&near, SEARCH_BUDGET).unwrap();
+    assert!(solution.expanded <= SEARCH_BUDGET);
+    for shift in &solution.shifts {
+        assert!(near.apply(shift));
+    }
+    assert!(near.is_solved());
+
+    // Every generated position counts against the budget, so even a failing search
+    // stops within seconds in a debug build
+    let mut far = PuzzleState::new(9);
+    for shift in shuffle(9, 10, &mut SeededRng::new(1)) {
+        far.apply(&shift);
+    }
+    let start = Instant::now();
+    solve_greedy(&far, SEARCH_BUDGET);
+    assert!(start.elapsed() < Duration::from_secs(15));
+}
+
+#[test]
+fn games_fall_back_to_greedy_solutions() {
+    let mut state = PuzzleState::new(4);
+    for shift in shuffle(4, 10, &mut SeededRng::new(1)) {
+        state.apply(&shift);
+    }
+    let solution = solve_for_game(&state, None).unwrap();
+    for shift in &solution.shifts {
+        assert!(state.apply(shift));
+    }
+    assert!(state.is_solved());
+}
+
+#[test]
+fn games_retrace_the_shuffle_of_large_boards() {
+    for seed in 0..5 {
+        let shifts = shuffle(5, 10, &mut SeededRng::new(seed));
+        let mut state = PuzzleState::new(5);
+        for shift in &shifts {
+            state.apply(shift);
+        }
+        let solution = solve_for_game(&state, Some(&shifts)).unwrap();
+        assert!(solution.shifts.len() <= shifts.len());
+        for shift in &solution.shifts {
+            assert!(state.apply(shift));
+        }
+        assert!(state.is_solved(), "seed {} is not solved", seed);
+    }
+}
+
+#[test]
+fn default_shuffles_of_every_size_can_be_auto_solved() {
+    for size in 2..=9 {
+        let shifts = shuffle(size, 10, &mut SeededRng::new(7));
+        let mut state = PuzzleState::new(size);
+        for shift in &shifts {
+            state.apply(shift);
+        }
+        let solution = solve_for_game(&state, Some(&shifts)).unwrap();
+        assert!(solution.shifts.len() <= MAX_AUTO_SOLVE_LENGTH);
+    }
+}
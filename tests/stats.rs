@@ -0,0 +1,46 @@
+use puzzle::stats::Stats;
+
+#[test]
+fn counting_starts_on_the_first_move() {
+    let mut stats = Stats::default();
+    stats.tick(1000.0);
+    assert_eq!(stats.moves(), 0);
+    assert_eq!(stats.elapsed(), 0.0);
+
+    stats.record_move();
+    stats.tick(1500.0);
+    stats.record_move();
+    assert_eq!(stats.moves(), 2);
+    assert_eq!(stats.elapsed(), 1500.0);
+    assert_eq!(stats.clock(), "0:01");
+}
+
+#[test]
+fn time_does_not_advance_while_paused_or_stopped() {
+    let mut stats = Stats::default();
+    stats.record_move();
+    stats.tick(100.0);
+    stats.pause();
+    stats.tick(100.0);
+    assert_eq!(stats.elapsed(), 100.0);
+
+    // A paused game carries on with the next move
+    stats.record_move();
+    stats.tick(100.0);
+    assert_eq!(stats.elapsed(), 200.0);
+
+    stats.stop();
+    stats.tick(100.0);
+    assert_eq!(stats.elapsed(), 200.0);
+}
+
+#[test]
+fn moves_after_stopping_are_not_counted() {
+    let mut stats = Stats::default();
+    stats.record_move();
+    stats.stop();
+    stats.record_move();
+    stats.tick(100.0);
+    assert_eq!(stats.moves(), 1);
+    assert_eq!(stats.elapsed(), 0.0);
+}
@@ -0,0 +1,103 @@
+use puzzle::board::Board;
+use puzzle::puzzle::Point;
+use puzzle::renderer::{Renderer, Transform};
+use puzzle::stats::Stats;
+use puzzle::svg::SvgRenderer;
+use puzzle::theme::Theme;
+
+#[test]
+fn renders_every_tile_as_a_translated_group() {
+    let board = Board::new(3, Theme::light());
+    let mut renderer = SvgRenderer::new();
+    board.render(&mut renderer, &Stats::default());
+    let svg = renderer.to_svg();
+
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.contains(r#"viewBox="0 0 1000 1000""#));
+    assert!(svg.contains(r#"preserveAspectRatio="xMidYMid meet""#));
+    assert!(svg.ends_with("</svg>"));
+    assert_eq!(svg.matches(r#"<g class="tile""#).count(), 9);
+    assert_eq!(svg.matches(r#"<g class="tile empty""#).count(), 6);
+    assert_eq!(svg.matches("<g ").count(), svg.matches("</g>").count());
+    assert!(svg.contains(r#"<g class="tile" transform="translate(200 200)">"#));
+    assert!(svg.contains(">5</text>"));
+}
+
+#[test]
+fn transform_alpha_and_text_are_written_as_attributes() {
+    let mut renderer = SvgRenderer::new();
+    renderer.set_transform(Transform::stretch(500.0, 500.0));
+    renderer.set_alpha(0.25);
+    Board::new(2, Theme::dark()).render_solved_overlay(&mut renderer);
+    let svg = renderer.to_svg();
+
+    assert!(svg.contains(r#"width="500" height="500""#));
+    assert!(svg.contains(r#"opacity="0.6""#));
+    assert!(svg.contains(">Solved!</text>"));
+
+    renderer.clear();
+    assert!(!renderer.to_svg().contains("<rect"));
+}
+
+#[test]
+fn picture_tiles_are_nested_viewports_of_the_picture() {
+    let mut board = Board::new(2, Theme::light());
+    board.set_picture(true);
+    let mut renderer = SvgRenderer::new();
+    renderer.set_picture(Some("cat.png?size=large&v=2".to_string()));
+    board.render(&mut renderer, &Stats::default());
+    let svg = renderer.to_svg();
+
+    assert_eq!(
+        svg.matches(r#"<image href="cat.png?size=large&amp;v=2""#)
+            .count(),
+        4
+    );
+    assert!(svg.contains(r#"viewBox="0.5 0.5 0.5 0.5""#));
+
+    renderer.set_picture(None);
+    renderer.clear();
+    board.render(&mut renderer, &Stats::default());
+    assert!(!renderer.to_svg().contains("<image"));
+}
+
+#[test]
+fn transform_offsets_are_margins_around_the_board() {
+    let transform = Transform {
+        scale: Point { x: 0.5, y: 0.5 },
+        offset: Point { x: 100.0, y: 0.0 },
+    };
+    let mut renderer = SvgRenderer::new();
+    renderer.set_transform(transform);
+    let svg = renderer.to_svg();
+
+    assert!(svg.contains(r#"viewBox="-200 0 1400 1000""#));
+    assert!(svg.contains(r#"width="700" height="500""#));
+    assert_eq!(
+        transform.to_board(Point { x: 350.0, y: 250.0 }),
+        Point { x: 500.0, y: 500.0 }
+    );
+}
+
+#[test]
+fn shifting_tiles_and_ticking_the_timer_keep_the_layout() {
+    let mut board = Board::new(3, Theme::light());
+    let mut stats = Stats::default();
+    let mut renderer = SvgRenderer::new();
+    board.render(&mut renderer, &stats);
+    let layout = renderer.layout().to_string();
+    let transforms = renderer.transforms().to_vec();
+    assert_eq!(transforms.len(), 15);
+    assert!(!layout.contains("transform="));
+    assert!(!layout.contains(">5</text>"));
+
+    let shift = board.state().col_shift(1).unwrap();
+    board.displace(&shift, 40.0);
+    stats.record_move();
+    renderer.clear();
+    board.render(&mut renderer, &stats);
+
+    assert_eq!(renderer.layout(), layout);
+    assert_ne!(renderer.transforms(), &transforms[..]);
+    assert!(renderer.texts().contains(&"1".to_string()));
+}
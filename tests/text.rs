@@ -0,0 +1,51 @@
+use puzzle::cursor::Cursor;
+use puzzle::puzzle::{Direction, PuzzleState, Shift};
+use puzzle::stats::Stats;
+use puzzle::text;
+
+#[test]
+fn renders_the_grid_below_the_stats() {
+    let state = PuzzleState::new(3);
+    let rendered = text::render(&state, None, &Stats::at(12, 65_000.0));
+    let lines: Vec<&str> = rendered.split('\n').collect();
+
+    assert_eq!(lines.len(), 1 + 5);
+    assert!(lines[0].starts_with("MOVES 12") && lines[0].ends_with("TIME 1:05"));
+    assert!(lines[2].contains("[1] [2] [3]"));
+    assert!(lines[4].contains("[7] [8] [9]"));
+}
+
+#[test]
+fn marks_the_cursor_and_pads_wide_labels() {
+    let mut state = PuzzleState::new(4);
+    state.apply(&state.row_shift(2).unwrap());
+    let mut cursor = Cursor::new();
+    let rendered = text::render(&state, Some(&cursor), &Stats::default());
+    assert!(rendered.contains("( 1)"));
+    assert!(rendered.contains("[16]"));
+
+    cursor.grabbed = true;
+    cursor.move_towards(&Direction::South, 4);
+    let rendered = text::render(&state, Some(&cursor), &Stats::default());
+    let shift = Shift {
+        idx: 2,
+        direction: state.row_shift(2).unwrap().direction,
+    };
+    assert_eq!(cursor.shift_towards(shift.direction), shift);
+    assert!(rendered.lines().nth(3).unwrap().contains('{'));
+}
+
+#[test]
+fn grids_round_trip_and_reject_invalid_positions() {
+    let mut state = PuzzleState::new(3);
+    state.apply(&state.col_shift(2).unwrap());
+    let grid = text::format_grid(&state);
+    assert_eq!(grid.lines().next(), Some(". _ . _ ."));
+    assert_eq!(text::parse_grid(&format!("\n{}\n\n", grid)), Some(state));
+
+    // A single tile is a valid position, but too small a game
+    assert_eq!(text::parse_grid(". _ .\n_ 1 .\n. . ."), None);
+    assert_eq!(text::parse_grid(". _ .\n_ 2 .\n. . ."), None);
+    assert_eq!(text::parse_grid(". _ .\n_ 1 .\n. ."), None);
+    assert_eq!(text::parse_grid(". _ .\n_ x .\n. . ."), None);
+}
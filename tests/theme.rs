@@ -0,0 +1,74 @@
+use puzzle::board::Board;
+use puzzle::renderer::{DrawCommand, RecordingRenderer};
+use puzzle::stats::Stats;
+use puzzle::svg::SvgRenderer;
+use puzzle::theme::{LabelStyle, Theme};
+
+fn render(theme: Theme) -> RecordingRenderer {
+    let mut renderer = RecordingRenderer::default();
+    Board::new(3, theme).render(&mut renderer, &Stats::default());
+    renderer
+}
+
+#[test]
+fn built_in_themes_are_found_by_name() {
+    assert_eq!(Theme::by_name("light"), Some(Theme::light()));
+    assert_eq!(Theme::by_name("dark"), Some(Theme::dark()));
+    assert_eq!(
+        Theme::by_name("high-contrast"),
+        Some(Theme::high_contrast())
+    );
+    assert_eq!(Theme::by_name("neon"), None);
+}
+
+#[test]
+fn custom_themes_fill_in_missing_fields_from_the_light_theme() {
+    let theme = Theme::from_json(
+        r#"{"tile": "rebeccapurple", "font_family": "Georgia", "label_style": "hidden"}"#,
+    )
+    .unwrap();
+    assert_eq!(theme.tile, "rebeccapurple");
+    assert_eq!(theme.font_family, "Georgia");
+    assert_eq!(theme.label_style, LabelStyle::Hidden);
+    assert_eq!(theme.background, Theme::light().background);
+
+    assert!(Theme::from_json(r#"{"label_style": "italic"}"#).is_err());
+}
+
+#[test]
+fn labels_are_drawn_in_the_theme_font_and_style() {
+    let renderer = render(Theme::high_contrast());
+    let label = renderer
+        .commands
+        .iter()
+        .find_map(|command| match command {
+            DrawCommand::Text {
+                text, size, family, ..
+            } if text == "1" => Some((*size, family.clone())),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(label, (0.72 * 200.0, "Verdana, sans-serif".to_string()));
+
+    let hidden = Theme {
+        label_style: LabelStyle::Hidden,
+        ..Theme::light()
+    };
+    assert_eq!(render(hidden).texts(), vec!["MOVES", "0", "TIME", "0:00"]);
+}
+
+#[test]
+fn tiles_have_the_theme_corner_radius() {
+    let rounded = render(Theme::dark());
+    assert!(rounded.commands.iter().any(|command| matches!(
+        command,
+        DrawCommand::FillRect(rect, colour) if rect.radius == 0.12 * 200.0 && *colour == Theme::dark().tile
+    )));
+
+    let mut svg = SvgRenderer::new();
+    Board::new(3, Theme::dark()).render(&mut svg, &Stats::default());
+    assert!(svg.to_svg().contains(r#"rx="24""#));
+    let mut svg = SvgRenderer::new();
+    Board::new(3, Theme::light()).render(&mut svg, &Stats::default());
+    assert!(!svg.to_svg().contains("rx="));
+}